@@ -1,3 +1,4 @@
+import math
 import fragmentcolor as plr
 
 scene = plr.Scene()
@@ -9,6 +10,8 @@ gaze = plr.Circle(
     border=0.01,
 )
 
+elapsed = 0.0
+
 
 def init():
     scene.target(window)
@@ -19,13 +22,15 @@ def init():
 
 
 def on_draw():
-    gaze.set_position(_position_for_time())
+    global elapsed
+    elapsed += window.clock().delta
+    gaze.set_position(_position_for_time(elapsed))
     scene.render()
     pass
 
 
-def _position_for_time():
-    return (0.5, 0.5)
+def _position_for_time(time):
+    return (0.5 + 0.25 * math.cos(time), 0.5 + 0.25 * math.sin(time))
 
 
 if __name__ == '__main__':
@@ -10,7 +10,6 @@ use fragmentcolor::{
     scene::{transform::TransformId, Object},
     FragmentColor,
 };
-use instant::Instant;
 
 const ROOT_SCALE: f32 = 2.0;
 const SCALE_LEVEL: f32 = 0.4;
@@ -72,6 +71,14 @@ fn main() {
     })
     .unwrap();
 
+    let mut window_2 = Window::new(WindowOptions {
+        title: "Cubes 2".to_string(),
+        size: (300, 300),
+        framerate: Some(60),
+        ..Default::default()
+    })
+    .unwrap();
+
     // Creates a new Camera (necessary for 3D scenes).
     let mut camera = Camera::new(CameraOptions {
         projection: Projection::perspective(45.0),
@@ -87,43 +94,37 @@ fn main() {
     // Attaches this Camera to the Window.
     scene.target_with_camera(&window, &camera);
 
+    // The second Window shows another Scene from the top. Each Window
+    // draws its own Scene at its own framerate: 15 and 60 FPS.
+    let mut top_scene = fragmentcolor::Scene::new();
+    let mut top_camera = Camera::new(CameraOptions {
+        projection: Projection::perspective(45.0),
+        z_near: 1.0,
+        z_far: 20.0,
+    });
+    top_camera.set_position(Vec3::from([0.0, -0.1, 10.0]));
+    top_camera.look_at(ORIGIN, UP_VECTOR);
+    top_scene.add(&mut top_camera);
+    top_scene.target_with_camera(&window_2, &top_camera);
+
     // Creates a cube mesh.
-    let cube = Primitive::cube(1.0).create_mesh();
+    let cube = Primitive::cube(1.0).create_mesh().unwrap();
 
-    // Creates all the cubes and adds them to the Scene.
-    let mut cubes = fill_scene(&LEVELS[..], &mut scene, cube.unwrap());
-    println!("Initialized {} cubes", cubes.len());
+    // Creates all the cubes and adds them to the Scenes.
+    let mut cubes = fill_scene(&LEVELS[..], &mut scene, cube.clone());
+    let mut top_cubes = fill_scene(&LEVELS[..], &mut top_scene, cube);
+    println!("Initialized {} cubes per Scene", cubes.len());
 
     // Let's go!
-    let mut moment = Instant::now();
     let state = window.state();
     window.on("draw", move |event| match event {
         Event::Draw => {
             let window = state.read().unwrap();
-            println!("FPS: {}", 1.0 / moment.elapsed().as_secs_f32());
-
-            let delta = moment.elapsed().as_secs_f32();
-            moment = Instant::now();
-
-            for cube in cubes.iter_mut() {
-                let level = &LEVELS[cube.level as usize];
-
-                // println!("=> updating transform id: {:?}...", cube.transform_id);
-                // println!(
-                //     "=> transform in scene: {:?}...",
-                //     scene.read_state()[cube.transform_id]
-                //
-                // ); @TODO investigate why TransformId is None
-                cube.object.pre_rotate(
-                    Vec3 {
-                        x: 0.0,
-                        y: 0.0,
-                        z: 1.0,
-                    },
-                    delta * level.speed,
-                );
-            }
+            let clock = window.clock();
+            let delta = clock.delta();
+            println!("Window 1 FPS: {}", clock.fps());
 
+            rotate(&mut cubes, delta);
             _ = scene.render();
 
             window.redraw();
@@ -134,10 +135,46 @@ fn main() {
         _ => {}
     });
 
+    let state_2 = window_2.state();
+    window_2.on("draw", move |event| match event {
+        Event::Draw => {
+            let window = state_2.read().unwrap();
+            let clock = window.clock();
+            println!("Window 2 FPS: {}", clock.fps());
+
+            rotate(&mut top_cubes, clock.delta());
+            _ = top_scene.render();
+
+            window.redraw();
+        }
+        _ => {}
+    });
+
     // Runs the application.
     FragmentColor::run();
 }
 
+fn rotate(cubes: &mut [CubePosition], delta: f32) {
+    for cube in cubes.iter_mut() {
+        let level = &LEVELS[cube.level as usize];
+
+        // println!("=> updating transform id: {:?}...", cube.transform_id);
+        // println!(
+        //     "=> transform in scene: {:?}...",
+        //     scene.read_state()[cube.transform_id]
+        //
+        // ); @TODO investigate why TransformId is None
+        cube.object.pre_rotate(
+            Vec3 {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            delta * level.speed,
+        );
+    }
+}
+
 struct Stack {
     parent: TransformId,
     level: u8,
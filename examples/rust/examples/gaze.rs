@@ -84,7 +84,7 @@ fn main() {
 
             gaze.set_position([x, y]);
 
-            scene.render();
+            _ = scene.render();
 
             window.redraw();
         }
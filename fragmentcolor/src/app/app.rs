@@ -5,7 +5,12 @@ use crate::{
         window::{IsWindow, WindowState, Windows},
         Event,
     },
-    renderer::{Renderer, RendererOptions},
+    components::Color,
+    math::geometry::Quad,
+    renderer::{
+        target::{IsRenderTarget, TargetId},
+        RenderTargetCollection, Renderer, RendererOptions,
+    },
     scene::{Scene, /*SceneState,*/ Scenes},
     Window,
 };
@@ -94,6 +99,152 @@ impl FragmentColor {
         renderer()
     }
 
+    /// Initializes the global Renderer without a Window.
+    ///
+    /// Headless apps, like CLI image generators and servers, can call
+    /// this first so the GPU is set up eagerly with the given options.
+    /// Otherwise, the Renderer is created lazily with default options
+    /// when the first Texture or Scene needs it. The adapter is chosen
+    /// without checking for compatibility with any window surface.
+    ///
+    /// Returns an error if the Renderer has already been initialized,
+    /// or if no GPU adapter or device is available, so CI and servers
+    /// without a GPU can handle it instead of panicking.
+    pub fn init_headless(options: RendererOptions) -> Result<(), Error> {
+        if RENDERER_INIT.is_completed() {
+            return Err("The Renderer is already initialized!".into());
+        }
+
+        let renderer = pollster::block_on(Renderer::new::<Window>(options, None))?;
+        if !init_renderer_with(renderer) {
+            return Err("The Renderer is already initialized!".into());
+        }
+
+        Ok(())
+    }
+
+    /// Resizes a Render Target and the Scene descriptions pointing to it.
+    ///
+    /// Windows with `auto_resize` enabled (the default) call this when
+    /// the OS resizes them. If you disabled it, call this from your
+    /// "resize" event callback to reconfigure the Window surface.
+    pub fn resize_target(target_id: TargetId, size: Quad) -> Result<(), Error> {
+        let app = Self::app();
+        let app = if let Ok(app) = app.try_read() {
+            app
+        } else {
+            return Err("Failed to acquire Read Lock for App!".into());
+        };
+
+        let state = app.state();
+        state.resize_target(target_id, size)
+    }
+
+    /// Returns information about the GPU adapter in use.
+    ///
+    /// Includes the backend (Vulkan, Metal, DX12, GL...), device name,
+    /// device type and driver. Useful for diagnostics and bug reports.
+    ///
+    /// Returns None if the Renderer has not been initialized yet,
+    /// which happens when the first Window or Texture is created.
+    pub fn adapter_info() -> Option<wgpu::AdapterInfo> {
+        with_renderer(|renderer| renderer.adapter_info()).ok()
+    }
+
+    /// Returns the graphics backend in use.
+    ///
+    /// Returns None if the Renderer has not been initialized yet.
+    pub fn backend() -> Option<wgpu::Backend> {
+        Self::adapter_info().map(|info| info.backend)
+    }
+
+    /// Returns the limits of the GPU device in use.
+    ///
+    /// Use it to check, for example, the maximum texture size or the
+    /// maximum storage buffer size before creating resources.
+    ///
+    /// `max_push_constant_size` is 0 unless the adapter supports
+    /// push constants, in which case it's the adapter's maximum.
+    ///
+    /// Returns the default wgpu limits if the Renderer
+    /// has not been initialized yet.
+    pub fn limits() -> wgpu::Limits {
+        with_renderer(|renderer| renderer.limits()).unwrap_or_default()
+    }
+
+    /// Returns how many textures are loaded in the GPU.
+    ///
+    /// Textures stay loaded until `Texture::free` is called, so a count
+    /// that keeps growing points to a leak. Includes the built-in
+    /// default and placeholder textures, but not Texture targets.
+    ///
+    /// Returns 0 if the Renderer has not been initialized yet.
+    pub fn texture_count() -> usize {
+        with_renderer(|renderer| renderer.texture_count()).unwrap_or(0)
+    }
+
+    /// Changes the color of the placeholder texture.
+    ///
+    /// Sprites pointing to a missing texture draw this 1x1 texture
+    /// instead, so the missing binding is visible. Defaults to magenta.
+    ///
+    /// Returns an error if the Renderer has not been initialized yet.
+    pub fn set_placeholder_texture(color: Color) -> Result<(), Error> {
+        with_renderer(|renderer| renderer.set_placeholder_texture(color))?
+    }
+
+    /// Sends GPU errors to a callback.
+    ///
+    /// wgpu reports validation and out of memory errors asynchronously,
+    /// so the callback runs when the GPU device notices them. Use it to
+    /// log or recover from a bad resource. On native, errors raised while
+    /// rendering are returned by `Scene::render()` instead.
+    ///
+    /// If `panic_on_error` is set in the RendererOptions, errors still
+    /// panic after the callback runs. Set it to false to recover.
+    ///
+    /// Returns an error if the Renderer has not been initialized yet.
+    pub fn on_gpu_error(callback: impl Fn(wgpu::Error) + Send + 'static) -> Result<(), Error> {
+        with_renderer(|renderer| renderer.on_error(callback))
+    }
+
+    /// Starts or stops measuring how long each RenderPass takes on the GPU.
+    ///
+    /// Requires the `TIMESTAMP_QUERY` feature, which is requested when
+    /// the adapter supports it. Returns false if it's not supported,
+    /// in which case `last_frame_timings` stays empty.
+    ///
+    /// Returns an error if the Renderer has not been initialized yet.
+    pub fn enable_timing(enabled: bool) -> Result<bool, Error> {
+        with_renderer(|renderer| renderer.enable_timing(enabled))
+    }
+
+    /// Returns the GPU time of each RenderPass of the last timed frame.
+    ///
+    /// Each entry pairs the pass and its target or camera with the
+    /// time it took, in milliseconds. The timings are read back after
+    /// the GPU finishes a frame, so they lag a frame or two behind.
+    ///
+    /// Returns an empty list if timing is disabled or not supported.
+    pub fn last_frame_timings() -> Vec<(String, f64)> {
+        with_renderer(|renderer| renderer.last_frame_timings()).unwrap_or_default()
+    }
+
+    /// Drives pending GPU work, like buffer mapping callbacks.
+    ///
+    /// Use `wgpu::Maintain::Poll` to check for finished work without
+    /// blocking from your own event loop, or `wgpu::Maintain::Wait`
+    /// to block until all submitted work is done.
+    ///
+    /// Returns true if the GPU queue is empty.
+    ///
+    /// # Platform-specific
+    /// On the Web, this function is a no-op. The browser drives
+    /// the GPU work and the callbacks by itself.
+    pub fn poll(maintain: wgpu::Maintain) -> Result<bool, Error> {
+        with_renderer(|renderer| renderer.poll(maintain))
+    }
+
     /// Runs the main event loop. This function blocks the thread
     /// and never returns, until the user closes all windows.
     ///
@@ -350,6 +501,37 @@ impl AppState {
         scenes.insert(&scene.id(), scene.state())
     }
 
+    /// Resizes a Render Target in the Renderer and updates
+    /// the Target descriptions of every Scene to match.
+    pub(crate) fn resize_target(&self, target_id: TargetId, size: Quad) -> Result<(), Error> {
+        let renderer = renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Renderer is locked. Cannot resize Render Target!".into());
+        };
+
+        if let Ok(mut targets) = renderer.write_targets() {
+            if let Some(target) = targets.get_mut(&target_id) {
+                target.resize(&renderer, size.to_wgpu_size())?;
+            } else {
+                return Err(format!("Render Target {:?} not found!", target_id).into());
+            }
+        } else {
+            return Err("Renderer Targets are locked. Cannot resize Render Target!".into());
+        };
+
+        let mut scenes = self.write_to_scenes_collection();
+        let keys = scenes.keys.clone();
+        for scene_id in keys.iter() {
+            if let Some(mut scene) = scenes.get_mut(scene_id) {
+                scene.resize_target(target_id, size)
+            }
+        }
+
+        Ok(())
+    }
+
     // @TODO use it
     // /// Removes a window from the Windows collection.
     // pub(crate) fn remove_scene(&self, scene: Scene) -> RemovedScene {
@@ -361,9 +543,14 @@ impl AppState {
         RendererOptions {
             force_software_rendering: self.options.renderer.force_software_rendering,
             power_preference: self.options.renderer.power_preference.clone(),
+            backends: self.options.renderer.backends.clone(),
             panic_on_error: self.options.renderer.panic_on_error,
             device_limits: self.options.renderer.device_limits.clone(),
             render_pass: self.options.renderer.render_pass.clone(),
+            blend_mode: self.options.renderer.blend_mode,
+            primitive: self.options.renderer.primitive,
+            depth: self.options.renderer.depth,
+            debug_labels: self.options.renderer.debug_labels,
         }
     }
 }
@@ -387,6 +574,10 @@ fn app(options: AppOptions) -> MainApp {
     }
 }
 
+/// Guards the one-time initialization of the global Renderer.
+static RENDERER_INIT: Once = Once::new();
+static mut RENDERER: MaybeUninit<MainRenderer> = MaybeUninit::uninit();
+
 /// Initializes or returns the global Renderer.
 ///
 /// This function will ensure compatibility with the provided Window(s).
@@ -396,11 +587,8 @@ fn app(options: AppOptions) -> MainApp {
 /// The parameters for this function are used only once
 /// and ignored in subsequent calls.
 fn get_or_init_renderer<W: IsWindow>(options: RendererOptions, window: Option<&W>) -> MainRenderer {
-    static mut RENDERER: MaybeUninit<MainRenderer> = MaybeUninit::uninit();
-    static ONCE: Once = Once::new();
-
     unsafe {
-        ONCE.call_once(|| {
+        RENDERER_INIT.call_once(|| {
             let renderer = pollster::block_on(Renderer::new(options, window))
                 .expect("Failed to create Renderer");
             let renderer = Arc::new(RwLock::new(renderer));
@@ -411,6 +599,22 @@ fn get_or_init_renderer<W: IsWindow>(options: RendererOptions, window: Option<&W
     }
 }
 
+/// Sets an already created Renderer as the global Renderer.
+///
+/// Returns false if the global Renderer was already initialized.
+fn init_renderer_with(renderer: Renderer) -> bool {
+    let mut initialized = false;
+
+    unsafe {
+        RENDERER_INIT.call_once(|| {
+            RENDERER.write(Arc::new(RwLock::new(renderer)));
+            initialized = true;
+        });
+    }
+
+    initialized
+}
+
 /// Initializes an Offscreen global Renderer.
 ///
 /// It uses any GPU adapter, without checking for compatibility with a Window.
@@ -422,3 +626,22 @@ fn get_or_init_offscreen_renderer(options: RendererOptions) -> MainRenderer {
 pub(crate) fn renderer() -> MainRenderer {
     get_or_init_offscreen_renderer(RendererOptions::default())
 }
+
+/// Runs a function with the main Renderer, without initializing it.
+///
+/// Returns an error if the Renderer has not been initialized
+/// yet, or if it is locked by another thread.
+fn with_renderer<T>(read: impl FnOnce(&Renderer) -> T) -> Result<T, Error> {
+    if !RENDERER_INIT.is_completed() {
+        return Err("The Renderer is not initialized yet!".into());
+    }
+
+    let renderer = renderer();
+    let renderer = if let Ok(renderer) = renderer.try_read() {
+        renderer
+    } else {
+        return Err("Failed to acquire Read Lock for Renderer!".into());
+    };
+
+    Ok(read(&renderer))
+}
@@ -0,0 +1,131 @@
+use instant::{Duration, Instant};
+
+/// How much each new frame weighs in the smoothed FPS
+const FPS_SMOOTHING: f32 = 0.1;
+
+/// Measures the time between the frames of a Window.
+///
+/// Each Window ticks its clock right before it emits a Draw event.
+/// Read `Window::clock()` in the draw callback for the delta since
+/// the previous frame, or for the frames per second.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrameClock {
+    last_frame: Option<Instant>,
+    delta: f32,
+    fps: f32,
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds between the last two frames. Zero before the second frame.
+    pub fn delta(&self) -> f32 {
+        self.delta
+    }
+
+    /// Frames per second, smoothed over the last frames to avoid jitter.
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    /// When the next frame should be drawn to keep the given frametime.
+    ///
+    /// Each Window has its own clock, so Windows with different
+    /// framerates are paced independently. Returns None if the
+    /// next frame is already scheduled after `now`.
+    pub(crate) fn next_frame_at(&self, frametime: Duration, now: Instant) -> Option<Instant> {
+        match self.last_frame {
+            Some(last_frame) if last_frame > now => None,
+            Some(last_frame) => Some((last_frame + frametime).max(now)),
+            None => Some(now),
+        }
+    }
+
+    /// Registers a frame drawn at the given time and returns the delta.
+    pub(crate) fn tick(&mut self, now: Instant) -> f32 {
+        self.delta = if let Some(last_frame) = self.last_frame {
+            now.saturating_duration_since(last_frame).as_secs_f32()
+        } else {
+            0.0
+        };
+        self.last_frame = Some(now);
+
+        if self.delta > 0.0 {
+            let fps = 1.0 / self.delta;
+            self.fps = if self.fps == 0.0 {
+                fps
+            } else {
+                self.fps + (fps - self.fps) * FPS_SMOOTHING
+            };
+        }
+
+        self.delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_frame_has_no_delta() {
+        let mut clock = FrameClock::new();
+
+        assert_eq!(clock.tick(Instant::now()), 0.0);
+        assert_eq!(clock.fps(), 0.0);
+    }
+
+    #[test]
+    fn measures_time_between_frames() {
+        let mut clock = FrameClock::new();
+        let start = Instant::now();
+
+        clock.tick(start);
+        let delta = clock.tick(start + Duration::from_millis(20));
+
+        assert!((delta - 0.02).abs() < 1e-6);
+        assert!((clock.fps() - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn smooths_fps() {
+        let mut clock = FrameClock::new();
+        let start = Instant::now();
+
+        clock.tick(start);
+        clock.tick(start + Duration::from_millis(20));
+        clock.tick(start + Duration::from_millis(30));
+
+        // 50 fps, then one frame at 100 fps
+        assert!((clock.fps() - 55.0).abs() < 1e-3);
+        assert!((clock.delta() - 0.01).abs() < 1e-6);
+    }
+
+    #[test]
+    fn windows_are_paced_independently() {
+        let start = Instant::now();
+        let mut windows = [
+            (FrameClock::new(), Duration::from_secs_f64(1.0 / 15.0), 0),
+            (FrameClock::new(), Duration::from_secs_f64(1.0 / 60.0), 0),
+        ];
+
+        // The Event Loop asks each Window for a redraw every millisecond
+        for millisecond in 0..1000 {
+            let now = start + Duration::from_millis(millisecond);
+            for (clock, frametime, frames) in windows.iter_mut() {
+                if let Some(at) = clock.next_frame_at(*frametime, now) {
+                    clock.tick(at);
+                    *frames += 1;
+                }
+            }
+        }
+
+        let [(slow, _, slow_frames), (fast, _, fast_frames)] = windows;
+        assert!((15..=16).contains(&slow_frames), "{} frames", slow_frames);
+        assert!((60..=61).contains(&fast_frames), "{} frames", fast_frames);
+        assert!((slow.fps() - 15.0).abs() < 0.1);
+        assert!((fast.fps() - 60.0).abs() < 0.1);
+    }
+}
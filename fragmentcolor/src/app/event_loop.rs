@@ -1,7 +1,7 @@
 use crate::{
     app::{events::Event, AppState, Container, EventProcessor},
     renderer::{
-        target::{IsRenderTarget, TargetId},
+        target::{Dimensions, TargetId},
         RenderContext, RenderTargetCollection,
     },
     FragmentColor, Quad,
@@ -69,8 +69,6 @@ type C<'c> = &'c mut ControlFlow;
 
 /// The main Event Loop
 pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<AppState>>) {
-    let mut last_update = Instant::now();
-
     let event_handler = Box::new(move |event: E, _elwt: W, control_flow: C| {
         let app = app.try_read();
 
@@ -124,42 +122,15 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                     // The size of the window has changed.
                     // Contains the client area's new dimensions.
                     WindowEvent::Resized(physical_size) => {
-                        let size = Quad::from_window_size(physical_size).to_wgpu_size();
+                        let size = Quad::from_window_size(physical_size);
 
                         if window.auto_resize {
-                            let renderer = FragmentColor::renderer();
-                            let renderer = if let Ok(renderer) = renderer.try_read() {
-                                renderer
-                            } else {
-                                log::error!("Renderer is locked. Cannot auto-resize Render Target for Window {:?}!", window_id);
-                                return;
-                            };
-
-                            if let Ok(mut targets) = renderer.write_targets() {
-                                if let Some(target) = targets.get_mut(&target_id) {
-                                    if let Err(result) = target.resize(&renderer, size) {
-                                        log::error!(
-                                            "Failed to auto-resize Render Target for Window {:?}! {:?}",
-                                            window_id,
-                                            result
-                                        );
-                                    }
-                                } else {
-                                    log::warn!("Target not found! Cannot auto-resize Render Target for Window {:?}!", window_id);
-                                };
-                            } else {
-                                log::error!("Renderer Targets are locked. Cannot auto-resize Render Target for Window {:?}!", window_id);
-                                return;
-                            };
-
-                            let mut scenes = app.write_to_scenes_collection();
-                            let keys = scenes.keys.clone();
-                            for scene_id in keys.iter() {
-                                if let Some(mut scene) = scenes.get_mut(scene_id) {
-                                    scene.resize_target(target_id, Quad::from_wgpu_size(size))
-                                } else {
-                                    continue;
-                                };
+                            if let Err(error) = app.resize_target(target_id, size) {
+                                log::error!(
+                                    "Failed to auto-resize Render Target for Window {:?}! {:?}",
+                                    window_id,
+                                    error
+                                );
                             }
                         }
 
@@ -189,31 +160,26 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                         scale_factor,
                         new_inner_size,
                     } => {
-                        let size = Quad::from_window_size(new_inner_size).to_wgpu_size();
+                        let size = Quad::from_window_size(new_inner_size);
+
+                        if let Ok(renderer) = FragmentColor::renderer().try_read() {
+                            let scaling = *scale_factor as f32;
+                            if let Err(error) = renderer.rescale_target(&target_id, scaling) {
+                                log::error!(
+                                    "Failed to update the scale factor of Window {:?}! {:?}",
+                                    window_id,
+                                    error
+                                );
+                            }
+                        };
 
                         if window.auto_resize {
-                            let renderer = FragmentColor::renderer();
-                            let renderer = if let Ok(renderer) = renderer.try_read() {
-                                renderer
-                            } else {
-                                log::error!("Renderer is locked. Cannot auto-rescale Render Target for Window {:?}!", window_id);
-                                return;
-                            };
-
-                            if let Ok(mut targets) = renderer.write_targets() {
-                                if let Some(target) = targets.get_mut(&target_id) {
-                                    _ = target.resize(&renderer, size);
-                                };
-                            };
-
-                            let mut scenes = app.write_to_scenes_collection();
-                            let keys = scenes.keys.clone();
-                            for scene_id in keys.iter() {
-                                if let Some(mut scene) = scenes.get_mut(scene_id) {
-                                    scene.resize_target(target_id, Quad::from_wgpu_size(size))
-                                } else {
-                                    continue;
-                                };
+                            if let Err(error) = app.resize_target(target_id, size) {
+                                log::error!(
+                                    "Failed to auto-rescale Render Target for Window {:?}! {:?}",
+                                    window_id,
+                                    error
+                                );
                             }
                         };
 
@@ -326,6 +292,9 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                     // The window gained or lost focus.
                     // The parameter is true if the window has gained focus, and false if it has lost focus.
                     WindowEvent::Focused(focused) => {
+                        if !focused {
+                            window.update_input(|input| input.release_all());
+                        }
                         window.call("focus", Event::Focus { focused: *focused })
                     }
 
@@ -381,6 +350,13 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                             }
                         }
 
+                        if let Some(key) = virtual_keycode {
+                            window.update_input(|input| match released {
+                                true => input.release_key(*key),
+                                false => input.press_key(*key),
+                            });
+                        }
+
                         match released {
                             true => window.call(
                                 "keyup",
@@ -410,9 +386,14 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                     // The cursor has moved on the window.
                     WindowEvent::CursorMoved {
                         device_id: _,
-                        position: _,
+                        position,
                         ..
-                    } => {}
+                    } => {
+                        let size = window.size();
+                        window.update_input(|input| {
+                            input.move_mouse(position.x as f32, position.y as f32, size)
+                        });
+                    }
 
                     // The cursor has entered the window.
                     WindowEvent::CursorEntered { device_id: _ } => {
@@ -435,10 +416,13 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                     // An mouse button press has been received.
                     WindowEvent::MouseInput {
                         device_id: _,
-                        state: _,
-                        button: _,
+                        state,
+                        button,
                         ..
-                    } => {}
+                    } => window.update_input(|input| match state {
+                        ElementState::Pressed => input.press_button(*button),
+                        ElementState::Released => input.release_button(*button),
+                    }),
 
                     // Touchpad magnification event with two-finger pinch gesture.
                     //
@@ -564,16 +548,21 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                 if let Some(frametime) = window.target_frametime {
                     let now = Instant::now();
 
+                    // Paced by the Window's own clock, so each Window keeps its framerate
                     let window_frametime = Duration::from_secs_f64(frametime);
-                    match window_frametime.checked_sub(last_update.elapsed()) {
-                        Some(wait_time) => {
-                            window.call_later(now + wait_time, "draw", Event::Draw);
+                    match window.clock().next_frame_at(window_frametime, now) {
+                        Some(draw_at) if draw_at > now => {
+                            window.tick(draw_at);
+                            window.call_later(draw_at, "draw", Event::Draw);
                         }
-                        None => {
+                        Some(_) => {
+                            window.tick(now);
                             window.call("draw", Event::Draw);
                         }
+                        None => {} // The next frame is already scheduled
                     };
                 } else {
+                    window.tick(Instant::now());
                     window.call("draw", Event::Draw);
                 };
             }
@@ -614,8 +603,6 @@ pub(crate) fn run_event_loop(event_loop: WinitEventLoop<Event>, app: Arc<RwLock<
                 if windows.len() == 0 {
                     *control_flow = ControlFlow::Exit;
                 }
-
-                last_update = Instant::now();
             }
 
             Winit::LoopDestroyed => {
@@ -92,6 +92,11 @@ pub enum Event {
 
     Closed,
     Destroyed,
+    /// Emitted when a Window should draw a new frame.
+    ///
+    /// Carries no data, so matching `Event::Draw` keeps working. Read the
+    /// frame timing with `Window::clock()` and the keyboard and mouse
+    /// state with `Window::input()`, both updated before this event.
     Draw,
     Exit,
 }
@@ -0,0 +1,117 @@
+use crate::{app::events::VirtualKey, Quad};
+use std::collections::HashSet;
+use winit::event::MouseButton;
+
+/// The keyboard and mouse state of a Window.
+///
+/// Updated by the Event Loop before the input events are emitted,
+/// so callbacks can query it instead of tracking the raw events.
+/// Read it with `Window::input()`, typically in the "draw" callback.
+#[derive(Clone, Debug, Default)]
+pub struct Input {
+    keys: HashSet<VirtualKey>,
+    buttons: HashSet<MouseButton>,
+    mouse: (f32, f32),
+    size: (f32, f32),
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the given key is pressed
+    pub fn is_key_down(&self, key: VirtualKey) -> bool {
+        self.keys.contains(&key)
+    }
+
+    /// Whether the given mouse button is pressed
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons.contains(&button)
+    }
+
+    /// Mouse position in physical pixels, from the top left corner
+    pub fn mouse(&self) -> (f32, f32) {
+        self.mouse
+    }
+
+    /// Mouse position in normalized device coordinates.
+    ///
+    /// Both axes go from -1.0 to 1.0, with the origin at the
+    /// center of the Window and the Y axis pointing up.
+    pub fn mouse_ndc(&self) -> (f32, f32) {
+        let (width, height) = self.size;
+        if width == 0.0 || height == 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let (x, y) = self.mouse;
+        (x / width * 2.0 - 1.0, 1.0 - y / height * 2.0)
+    }
+
+    pub(crate) fn press_key(&mut self, key: VirtualKey) {
+        self.keys.insert(key);
+    }
+
+    pub(crate) fn release_key(&mut self, key: VirtualKey) {
+        self.keys.remove(&key);
+    }
+
+    pub(crate) fn press_button(&mut self, button: MouseButton) {
+        self.buttons.insert(button);
+    }
+
+    pub(crate) fn release_button(&mut self, button: MouseButton) {
+        self.buttons.remove(&button);
+    }
+
+    pub(crate) fn move_mouse(&mut self, x: f32, y: f32, window_size: Quad) {
+        self.mouse = (x, y);
+        self.size = (window_size.width_f32(), window_size.height_f32());
+    }
+
+    /// Releases everything, as the Window won't get the
+    /// release events while it's out of focus
+    pub(crate) fn release_all(&mut self) {
+        self.keys.clear();
+        self.buttons.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_pressed_keys_and_buttons() {
+        let mut input = Input::new();
+
+        input.press_key(VirtualKey::Space);
+        input.press_button(MouseButton::Left);
+        assert!(input.is_key_down(VirtualKey::Space));
+        assert!(input.is_button_down(MouseButton::Left));
+        assert!(!input.is_key_down(VirtualKey::A));
+
+        input.release_key(VirtualKey::Space);
+        assert!(!input.is_key_down(VirtualKey::Space));
+
+        input.release_all();
+        assert!(!input.is_button_down(MouseButton::Left));
+    }
+
+    #[test]
+    fn converts_mouse_to_ndc() {
+        let mut input = Input::new();
+        let size = Quad::from_size(200, 100);
+
+        input.move_mouse(100.0, 50.0, size);
+        assert_eq!(input.mouse_ndc(), (0.0, 0.0));
+
+        input.move_mouse(0.0, 0.0, size);
+        assert_eq!(input.mouse_ndc(), (-1.0, 1.0));
+
+        input.move_mouse(200.0, 100.0, size);
+        assert_eq!(input.mouse(), (200.0, 100.0));
+        assert_eq!(input.mouse_ndc(), (1.0, -1.0));
+    }
+}
@@ -24,6 +24,11 @@
 /// ```
 pub mod app;
 
+/// Frame timing helper.
+///
+/// Measures the delta time and the frames per second of a Window.
+pub mod clock;
+
 /// Defines a list of commands that can be issued to the App's Event Loop.
 ///
 /// Users can send events to the App by calling `app.command()`.
@@ -55,6 +60,11 @@ pub(crate) mod event_loop;
 /// mainly Window events and the Draw event.
 pub mod events;
 
+/// Keyboard and mouse state.
+///
+/// Tracks the pressed keys, mouse buttons and mouse position of a Window.
+pub mod input;
+
 /// Handy internal macro to implement the `Container` trait for a type.
 pub(super) mod macros;
 
@@ -75,8 +85,10 @@ pub(crate) mod panics;
 pub mod window;
 
 pub use app::*;
+pub use clock::*;
 pub use commands::*;
 pub use container::*;
 pub use events::*;
+pub use input::*;
 pub use meta::*;
 pub use window::*;
@@ -1,11 +1,13 @@
 use crate::{
     app::{
+        clock::FrameClock,
         events::{Callback, CallbackFn, Event},
+        input::Input,
         panics, FragmentColor,
     },
     math::geometry::Quad,
     renderer::target::Dimensions,
-    DescribesTarget, RenderTargetDescription,
+    DescribesTarget, RenderTargetDescription, TargetId,
 };
 use instant::Instant;
 use instant::SystemTime;
@@ -58,11 +60,15 @@ pub struct WindowState {
     pub close_on_esc: bool,
     pub hovered_files: HashMap<u128, PathBuf>,
     pub target_frametime: Option<f64>,
+    pub(crate) present_mode: String,
+    pub(crate) alpha_mode: String,
     pub(crate) instance: winit::window::Window,
     reverse_lookup: HashMap<PathBuf, u128>,
     callbacks: HashMap<String, Vec<Callback<Event>>>,
     callstack: RwLock<CallStack>,
     scheduled: RwLock<BTreeMap<Instant, (String, Event)>>,
+    clock: RwLock<FrameClock>,
+    input: RwLock<Input>,
 }
 
 unsafe impl Send for WindowState {}
@@ -206,6 +212,42 @@ impl WindowState {
         self.instance.request_redraw();
     }
 
+    /// The timing of this Window's frames
+    pub fn clock(&self) -> FrameClock {
+        if let Ok(clock) = self.clock.read() {
+            *clock
+        } else {
+            FrameClock::default()
+        }
+    }
+
+    /// Registers a frame drawn at the given time
+    pub(crate) fn tick(&self, at: Instant) {
+        if let Ok(mut clock) = self.clock.try_write() {
+            clock.tick(at);
+        } else {
+            log::error!("Failed to acquire Write Lock for the Frame Clock!");
+        }
+    }
+
+    /// The keyboard and mouse state of this Window
+    pub fn input(&self) -> Input {
+        if let Ok(input) = self.input.read() {
+            input.clone()
+        } else {
+            Input::default()
+        }
+    }
+
+    /// Applies an input event to this Window's Input state
+    pub(crate) fn update_input(&self, update: impl FnOnce(&mut Input)) {
+        if let Ok(mut input) = self.input.try_write() {
+            update(&mut input)
+        } else {
+            log::error!("Failed to acquire Write Lock for the Window Input!");
+        }
+    }
+
     pub fn get_hovered_file(&self, index: u128) -> Option<String> {
         self.hovered_files
             .get(&index)
@@ -334,6 +376,8 @@ pub struct WindowOptions {
     pub auto_resize: bool,
     pub close_on_esc: bool,
     pub framerate: Option<u32>,
+    pub present_mode: String, // see PRESENT_MODES; unsupported modes fall back to "vsync"
+    pub alpha_mode: String,   // see ALPHA_MODES; "premultiplied" makes the window transparent
 }
 
 impl Default for WindowOptions {
@@ -349,6 +393,8 @@ impl Default for WindowOptions {
             auto_resize: true,
             close_on_esc: true,
             framerate: None,
+            present_mode: "default".to_string(),
+            alpha_mode: "default".to_string(),
         }
     }
 }
@@ -393,6 +439,10 @@ impl Window {
             .with_fullscreen(fullscreen)
             .with_decorations(options.decorations)
             .with_resizable(options.resizable)
+            .with_transparent(matches!(
+                options.alpha_mode.as_str(),
+                "premultiplied" | "postmultiplied"
+            ))
             .build(app.lock_event_loop().window_target())?;
 
         window.set_min_inner_size(
@@ -413,11 +463,15 @@ impl Window {
                 auto_resize: options.auto_resize,
                 close_on_esc: options.close_on_esc,
                 target_frametime: framerate_to_frametime(options.framerate),
+                present_mode: options.present_mode,
+                alpha_mode: options.alpha_mode,
                 hovered_files: HashMap::new(),
                 reverse_lookup: HashMap::new(),
                 callbacks: HashMap::new(),
                 callstack: RwLock::new(Vec::new()),
                 scheduled: RwLock::new(BTreeMap::new()),
+                clock: RwLock::new(FrameClock::new()),
+                input: RwLock::new(Input::new()),
             })),
         };
 
@@ -489,6 +543,32 @@ impl Window {
         self
     }
 
+    /// The present mode chosen for this Window's surface.
+    ///
+    /// It can differ from the requested one when the
+    /// platform doesn't support it. Returns None if the
+    /// Window has no surface.
+    pub fn present_mode(&self) -> Option<wgpu::PresentMode> {
+        let renderer = FragmentColor::renderer();
+        let renderer = renderer.try_read().ok()?;
+        let config = renderer.surface_config(&TargetId::Window(self.id()))?;
+
+        Some(config.present_mode)
+    }
+
+    /// The alpha mode chosen for this Window's surface.
+    ///
+    /// It can differ from the requested one when the
+    /// platform doesn't support it. Returns None if the
+    /// Window has no surface.
+    pub fn alpha_mode(&self) -> Option<wgpu::CompositeAlphaMode> {
+        let renderer = FragmentColor::renderer();
+        let renderer = renderer.try_read().ok()?;
+        let config = renderer.surface_config(&TargetId::Window(self.id()))?;
+
+        Some(config.alpha_mode)
+    }
+
     pub fn run(&mut self) {
         FragmentColor::run();
     }
@@ -497,6 +577,30 @@ impl Window {
         self.read_state().redraw();
     }
 
+    /// How many physical pixels make one logical pixel.
+    ///
+    /// HiDPI displays, like Retina, use 2.0 or more. The Window
+    /// surface and the `resolution` of its shaders always use the
+    /// physical size, which is the logical size times this factor.
+    pub fn scale_factor(&self) -> f32 {
+        self.scaling()
+    }
+
+    /// The size of this Window in logical pixels
+    pub fn logical_size(&self) -> Quad {
+        self.size().scale(1.0 / self.scale_factor())
+    }
+
+    /// The delta time and frames per second of this Window
+    pub fn clock(&self) -> FrameClock {
+        self.read_state().clock()
+    }
+
+    /// The pressed keys, mouse buttons and mouse position of this Window
+    pub fn input(&self) -> Input {
+        self.read_state().input()
+    }
+
     pub fn on(&mut self, event_name: &str, callback: impl CallbackFn<Event> + 'static) {
         let callback = Arc::new(RwLock::new(callback));
         self.write_state().on(event_name, callback)
@@ -20,6 +20,10 @@ impl Serialize for Color {
 const GAMMA: f32 = 2.2;
 
 impl Color {
+    /// Creates a Color from components between 0.0 and 1.0.
+    ///
+    /// Components are rounded to the nearest byte, so 0.5 becomes 128.
+    /// Every byte survives a round trip through the float accessors.
     pub fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
         Self(
             (Self::import(red) << 24)
@@ -54,6 +58,75 @@ impl Color {
         ))
     }
 
+    /// Creates an opaque color from hue (in degrees), saturation and lightness.
+    ///
+    /// The hue wraps around at 360°, saturation and lightness are clamped to 0.0..1.0.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let s = saturation.clamp(0.0, 1.0);
+        let l = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let [r, g, b] = hue_to_rgb(hue, chroma);
+        let m = l - chroma / 2.0;
+
+        Self::new(r + m, g + m, b + m, 1.0)
+    }
+
+    /// Creates an opaque color from hue (in degrees), saturation and value.
+    ///
+    /// The hue wraps around at 360°, saturation and value are clamped to 0.0..1.0.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let s = saturation.clamp(0.0, 1.0);
+        let v = value.clamp(0.0, 1.0);
+
+        let chroma = v * s;
+        let [r, g, b] = hue_to_rgb(hue, chroma);
+        let m = v - chroma;
+
+        Self::new(r + m, g + m, b + m, 1.0)
+    }
+
+    /// Returns the hue (in degrees), saturation and lightness of this color.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    /// Returns the hue (in degrees), saturation and value of this color.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+
+        (hue, saturation, max)
+    }
+
+    fn hue_max_min(self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.red(), self.green(), self.blue());
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        (hue, max, min)
+    }
+
     pub fn red(self) -> f32 {
         self.export(3)
     }
@@ -83,8 +156,87 @@ impl Color {
         ]
     }
 
+    /// Converts sRGB-encoded components (i.e. from CSS) to linear.
+    ///
+    /// Alpha is left untouched.
+    pub fn to_linear(self) -> Self {
+        Self::new(
+            srgb_to_linear(self.red()),
+            srgb_to_linear(self.green()),
+            srgb_to_linear(self.blue()),
+            self.alpha(),
+        )
+    }
+
+    /// Converts linear components to sRGB encoding.
+    ///
+    /// Alpha is left untouched.
+    pub fn to_srgb(self) -> Self {
+        Self::new(
+            linear_to_srgb(self.red()),
+            linear_to_srgb(self.green()),
+            linear_to_srgb(self.blue()),
+            self.alpha(),
+        )
+    }
+
+    /// Returns the same color with a different alpha.
+    pub fn with_alpha(self, alpha: f32) -> Self {
+        Self((self.0 & 0xFFFFFF00) | Self::import(alpha))
+    }
+
+    /// Interpolates between two colors.
+    ///
+    /// The color components are interpolated in linear space, which
+    /// avoids the dark bands of a naive sRGB blend. `t` is clamped
+    /// between 0.0 (this color) and 1.0 (the other color).
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| {
+            let a = srgb_to_linear(a);
+            let b = srgb_to_linear(b);
+            linear_to_srgb(a + (b - a) * t)
+        };
+
+        Self::new(
+            mix(self.red(), other.red()),
+            mix(self.green(), other.green()),
+            mix(self.blue(), other.blue()),
+            self.alpha() + (other.alpha() - self.alpha()) * t,
+        )
+    }
+
+    /// Mixes this color with black by `amount` (0.0 to 1.0), keeping its alpha.
+    pub fn darken(self, amount: f32) -> Self {
+        self.lerp(&Self(0x000000FF).with_alpha(self.alpha()), amount)
+    }
+
+    /// Mixes this color with white by `amount` (0.0 to 1.0), keeping its alpha.
+    pub fn lighten(self, amount: f32) -> Self {
+        self.lerp(&Self(0xFFFFFFFF).with_alpha(self.alpha()), amount)
+    }
+
+    /// Converts this color to a clear value for a target with the given format.
+    ///
+    /// Colors are treated as sRGB, like in CSS. sRGB targets encode the
+    /// values they receive, so the color is linearized first to avoid
+    /// washed-out clears. Linear targets receive the values unchanged.
+    pub fn to_wgpu_color(self, format: wgpu::TextureFormat) -> wgpu::Color {
+        if !format.is_srgb() {
+            return self.into();
+        }
+
+        wgpu::Color {
+            r: srgb_to_linear(self.red()) as f64,
+            g: srgb_to_linear(self.green()) as f64,
+            b: srgb_to_linear(self.blue()) as f64,
+            a: self.alpha() as f64,
+        }
+    }
+
+    // Rounds instead of truncating, so the float accessors round trip
     fn import(value: f32) -> u32 {
-        (value.clamp(0.0, 1.0) * 255.0) as u32
+        (value.clamp(0.0, 1.0) * 255.0).round() as u32
     }
 
     fn export(self, index: u32) -> f32 {
@@ -92,6 +244,37 @@ impl Color {
     }
 }
 
+/// RGB components of a hue with the given chroma, before adding the lightness offset
+fn hue_to_rgb(hue: f32, chroma: f32) -> [f32; 3] {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+
+    match h as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 impl From<Color> for wgpu::Color {
     fn from(c: Color) -> Self {
         Self {
@@ -114,3 +297,136 @@ impl From<Color> for [f32; 4] {
         c.to_array()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    #[test]
+    fn parses_css_colors() {
+        let cases = [
+            ("rebeccapurple", 0x663399ff),
+            ("#f80", 0xff8800ff),
+            ("#f80c", 0xff8800cc),
+            ("#ff8800", 0xff8800ff),
+            ("#ff8800cc", 0xff8800cc),
+            ("rgba(255, 136, 0, 0.8)", 0xff8800cc),
+        ];
+
+        for (css, expected) in cases {
+            let color = Color::from_css(css).unwrap();
+            assert_eq!(color, Color(expected), "{}", css);
+        }
+    }
+
+    #[test]
+    fn css_colors_convert_to_floats() {
+        let color = Color::from_css("#ff8800cc").unwrap();
+        let [r, g, b, a] = color.to_array();
+
+        assert_eq!(r, 1.0);
+        assert!((g - 136.0 / 255.0).abs() < f32::EPSILON);
+        assert_eq!(b, 0.0);
+        assert!((a - 0.8).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn converts_between_srgb_and_linear() {
+        let color = Color::from_css("#808080").unwrap();
+
+        let linear = color.to_linear();
+        assert_eq!(linear, Color(0x373737ff));
+        assert_eq!(linear.to_srgb(), color);
+
+        assert_eq!(Color(0xffffff80).to_linear(), Color(0xffffff80));
+        assert_eq!(Color(0x000000ff).to_srgb(), Color(0x000000ff));
+    }
+
+    #[test]
+    fn clear_color_follows_target_format() {
+        let color = Color::from_css("#808080").unwrap();
+
+        let linear = color.to_wgpu_color(wgpu::TextureFormat::Rgba8Unorm);
+        assert!((linear.r - 128.0 / 255.0).abs() < 1e-6);
+
+        let srgb = color.to_wgpu_color(wgpu::TextureFormat::Rgba8UnormSrgb);
+        assert!((srgb.r - 0.2158605).abs() < 1e-4);
+        assert_eq!(srgb.a, 1.0);
+    }
+
+    #[test]
+    fn lerp_endpoints_and_midpoint() {
+        let black = Color(0x000000ff);
+        let white = Color(0xffffff00);
+
+        assert_eq!(black.lerp(&white, 0.0), black);
+        assert_eq!(black.lerp(&white, 1.0), white);
+
+        // halfway in linear space is brighter than #808080 in sRGB
+        let middle = black.lerp(&white, 0.5);
+        assert_eq!(middle, Color(0xbcbcbc80));
+
+        // t is clamped
+        assert_eq!(black.lerp(&white, 2.0), white);
+    }
+
+    #[test]
+    fn alpha_and_shades() {
+        let color = Color::from_css("#ff8800").unwrap();
+
+        assert_eq!(color.with_alpha(0.5), Color(0xff880080));
+        assert_eq!(color.darken(0.0), color);
+        assert_eq!(color.darken(1.0), Color(0x000000ff));
+        assert_eq!(color.lighten(1.0), Color(0xffffffff));
+        assert_eq!(color.with_alpha(0.0).lighten(1.0), Color(0xffffff00));
+        assert!(color.darken(0.5).red() < color.red());
+    }
+
+    #[test]
+    fn converts_from_hsl_and_hsv() {
+        assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), Color(0xff0000ff));
+        assert_eq!(Color::from_hsl(120.0, 1.0, 0.5), Color(0x00ff00ff));
+        assert_eq!(Color::from_hsl(240.0, 1.0, 0.5), Color(0x0000ffff));
+        assert_eq!(Color::from_hsv(60.0, 1.0, 1.0), Color(0xffff00ff));
+
+        // hue wraps around and the other components are clamped
+        assert_eq!(Color::from_hsl(360.0, 1.0, 0.5), Color(0xff0000ff));
+        assert_eq!(Color::from_hsl(-120.0, 1.0, 0.5), Color(0x0000ffff));
+        assert_eq!(Color::from_hsv(0.0, 2.0, -1.0), Color(0x000000ff));
+    }
+
+    #[test]
+    fn hsl_and_hsv_round_trip() {
+        let close = |a: (f32, f32, f32), b: (f32, f32, f32)| {
+            (a.0 - b.0).abs() < 1.0 && (a.1 - b.1).abs() < 0.01 && (a.2 - b.2).abs() < 0.01
+        };
+
+        let color = Color::from_css("rebeccapurple").unwrap();
+        let (h, s, l) = color.to_hsl();
+        assert!(close((h, s, l), (270.0, 0.5, 0.4)), "{:?}", (h, s, l));
+        assert_eq!(Color::from_hsl(h, s, l), color);
+
+        let (h, s, v) = color.to_hsv();
+        assert!(close((h, s, v), (270.0, 0.667, 0.6)), "{:?}", (h, s, v));
+        assert_eq!(Color::from_hsv(h, s, v), color);
+
+        assert_eq!(Color(0x808080ff).to_hsl(), (0.0, 0.0, 128.0 / 255.0));
+    }
+
+    #[test]
+    fn bytes_round_trip_through_floats() {
+        for byte in 0..=255u32 {
+            let color = Color(byte << 24 | byte << 16 | byte << 8 | byte);
+            let floats = color.to_array();
+            assert_eq!(Color::from_rgba(floats), color, "byte {} changed", byte);
+        }
+
+        // Halfway values round up
+        assert_eq!(Color::new(0.5, 0.5, 0.5, 1.0), Color(0x808080ff));
+    }
+
+    #[test]
+    fn rejects_invalid_css() {
+        assert!(Color::from_css("not-a-color").is_err());
+    }
+}
@@ -1,6 +1,12 @@
 use crate::scene::macros::api_object;
 use crate::{Border, Bounds, Color, Object, Quad, Renderable2D, SceneObject, ShapeFlag};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+type Error = Box<dyn std::error::Error>;
+
+/// How deep `#include` directives can be nested
+const MAX_INCLUDE_DEPTH: usize = 32;
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Shader(String);
@@ -28,6 +34,243 @@ impl Shader {
 
         shader
     }
+
+    /// The source code of this shader, after defines and includes.
+    pub fn source(&self) -> &str {
+        &self.0
+    }
+
+    /// Creates a shader replacing every identifier that matches the
+    /// name of a define with its value, like a C `#define`.
+    ///
+    /// `Shader::new_with_defines(source, &[("MAX_LIGHTS", "8")])` turns
+    /// `array<Light, MAX_LIGHTS>` into `array<Light, 8>`. Only whole
+    /// identifiers are replaced, so `MAX_LIGHTS_2` is left untouched.
+    ///
+    /// Returns an error if the resulting WGSL doesn't parse or validate.
+    pub fn new_with_defines(source: &str, defines: &[(&str, &str)]) -> Result<Object<Self>, Error> {
+        let source = substitute_defines(source, defines);
+        validate_wgsl(&source)?;
+
+        Ok(Self::new(&source))
+    }
+
+    /// Loads a shader from a file, replacing `#include "path"` lines
+    /// with the contents of the included file.
+    ///
+    /// Includes are resolved relative to the file that includes them,
+    /// and then relative to each of the `include_dirs`, in order.
+    ///
+    /// Returns an error if an include can't be resolved, or if the
+    /// resulting WGSL doesn't parse or validate.
+    pub fn from_path_with_includes(
+        path: impl AsRef<Path>,
+        include_dirs: &[PathBuf],
+    ) -> Result<Object<Self>, Error> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read shader {}: {}", path.display(), e))?;
+
+        let mut stack = vec![path.canonicalize()?];
+        let source = resolve_includes(&source, path, include_dirs, &mut stack)?;
+        validate_wgsl(&source).map_err(|e| format!("Invalid shader {}: {}", path.display(), e))?;
+
+        Ok(Self::new(&source))
+    }
+}
+
+/// Parses and validates a WGSL source, so preprocessing mistakes
+/// are reported where the shader is created.
+fn validate_wgsl(source: &str) -> Result<(), Error> {
+    let module = naga::front::wgsl::parse_str(source).map_err(|e| e.emit_to_string(source))?;
+
+    naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|e| format!("{}", e.as_inner()))?;
+
+    Ok(())
+}
+
+/// Replaces whole identifiers in a shader source by their defined values
+fn substitute_defines(source: &str, defines: &[(&str, &str)]) -> String {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut output = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find(is_identifier) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find(|c: char| !is_identifier(c)).unwrap_or(rest.len());
+        let token = &rest[..end];
+
+        match defines.iter().find(|(name, _)| *name == token) {
+            Some((_, value)) => output.push_str(value),
+            None => output.push_str(token),
+        }
+
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+
+    output
+}
+
+/// Recursively replaces `#include "path"` lines in a shader source.
+///
+/// The stack holds the files currently being included,
+/// so circular includes can be reported instead of looping.
+fn resolve_includes(
+    source: &str,
+    path: &Path,
+    include_dirs: &[PathBuf],
+    stack: &mut Vec<PathBuf>,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(source.len());
+
+    for (index, line) in source.lines().enumerate() {
+        let location = format!("{}:{}", path.display(), index + 1);
+
+        let include = match line.trim().strip_prefix("#include") {
+            Some(include) => include.trim(),
+            None => {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+        };
+
+        let name = include
+            .strip_prefix('"')
+            .and_then(|include| include.strip_suffix('"'))
+            .ok_or_else(|| format!("{}: expected #include \"path\"", location))?;
+
+        if stack.len() >= MAX_INCLUDE_DEPTH {
+            return Err(format!(
+                "{}: includes are nested deeper than {} levels",
+                location, MAX_INCLUDE_DEPTH
+            )
+            .into());
+        }
+
+        let parent_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let included_path = std::iter::once(&parent_dir)
+            .chain(include_dirs)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| format!("{}: could not find include \"{}\"", location, name))?;
+
+        let canonical_path = included_path.canonicalize()?;
+        if stack.contains(&canonical_path) {
+            return Err(format!("{}: circular include of \"{}\"", location, name).into());
+        }
+
+        let included_source = std::fs::read_to_string(&included_path)
+            .map_err(|e| format!("{}: could not read include \"{}\": {}", location, name, e))?;
+
+        stack.push(canonical_path);
+        let included = resolve_includes(&included_source, &included_path, include_dirs, stack)?;
+        stack.pop();
+
+        output.push_str(&included);
+    }
+
+    Ok(output)
 }
 
 api_object!(Shader);
+
+impl Object<Shader> {
+    /// The source code of this shader, after defines and includes.
+    pub fn source(&self) -> String {
+        self.object().source().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fragmentcolor-{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("lib")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn substitutes_whole_identifiers() {
+        let source = "var<uniform> lights: array<Light, MAX_LIGHTS>;\n\
+                      @compute @workgroup_size(WORKGROUP_SIZE)\n\
+                      fn main() { let MAX_LIGHTS_2 = MAX_LIGHTS*2; }";
+
+        let output = substitute_defines(source, &[("MAX_LIGHTS", "8"), ("WORKGROUP_SIZE", "64")]);
+
+        assert_eq!(
+            output,
+            "var<uniform> lights: array<Light, 8>;\n\
+             @compute @workgroup_size(64)\n\
+             fn main() { let MAX_LIGHTS_2 = 8*2; }"
+        );
+        assert_eq!(substitute_defines(source, &[]), source);
+    }
+
+    #[test]
+    fn validates_the_substituted_source() {
+        let source = "const MAX_LIGHTS: u32 = LIGHTS;\n\
+                      @fragment fn main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }";
+
+        assert!(validate_wgsl(&substitute_defines(source, &[("LIGHTS", "8u")])).is_ok());
+
+        let error = Shader::new_with_defines(source, &[("LIGHTS", "eight")]).unwrap_err();
+        assert!(error.to_string().contains("eight"), "{}", error);
+    }
+
+    #[test]
+    fn resolves_nested_includes() {
+        let dir = temp_dir("shader-includes");
+        fs::write(dir.join("main.wgsl"), "#include \"a.wgsl\"\nfn main() {}\n").unwrap();
+        fs::write(dir.join("a.wgsl"), "#include \"b.wgsl\"\nfn a() {}\n").unwrap();
+        fs::write(dir.join("lib").join("b.wgsl"), "fn b() {}\n").unwrap();
+
+        let path = dir.join("main.wgsl");
+        let mut stack = vec![path.canonicalize().unwrap()];
+        let source = fs::read_to_string(&path).unwrap();
+        let output = resolve_includes(&source, &path, &[dir.join("lib")], &mut stack).unwrap();
+
+        assert_eq!(output, "fn b() {}\nfn a() {}\nfn main() {}\n");
+        assert_eq!(stack.len(), 1);
+    }
+
+    #[test]
+    fn reports_circular_and_missing_includes() {
+        let dir = temp_dir("shader-include-errors");
+        fs::write(dir.join("a.wgsl"), "#include \"b.wgsl\"\n").unwrap();
+        fs::write(dir.join("b.wgsl"), "\n#include \"a.wgsl\"\n").unwrap();
+        fs::write(dir.join("c.wgsl"), "#include \"missing.wgsl\"\n").unwrap();
+
+        let resolve = |name: &str| {
+            let path = dir.join(name);
+            let source = fs::read_to_string(&path).unwrap();
+            let mut stack = vec![path.canonicalize().unwrap()];
+            resolve_includes(&source, &path, &[], &mut stack)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let circular = resolve("a.wgsl");
+        assert!(
+            circular.contains("b.wgsl:2: circular include"),
+            "{}",
+            circular
+        );
+
+        let missing = resolve("c.wgsl");
+        assert!(missing.contains("c.wgsl:1: could not find"), "{}", missing);
+    }
+}
@@ -6,7 +6,7 @@ use std::iter;
 
 pub(super) fn cube(size: f32) -> Primitive {
     cuboid(
-        vertex::VertexTypes::empty(),
+        vertex::VertexTypes::UV,
         Vec3 {
             x: size,
             y: size,
@@ -31,7 +31,8 @@ pub(super) fn cuboid<V: Into<Vec3>>(vertex_types: vertex::VertexTypes, dimension
         (dimensions.x * dimensions.x + dimensions.y * dimensions.y + dimensions.z * dimensions.z)
             .sqrt();
 
-    if vertex_types.contains(vertex::VertexTypes::NORMAL) {
+    // Texture coordinates need a separate set of vertices per face, like normals
+    if vertex_types.intersects(vertex::VertexTypes::NORMAL | vertex::VertexTypes::UV) {
         let positions = vec![
             // top (0, 0, 1)
             pos(-1, -1, 1),
@@ -77,6 +78,13 @@ pub(super) fn cuboid<V: Into<Vec3>>(vertex_types: vertex::VertexTypes, dimension
         .flat_map(|&n| iter::repeat(n).take(4))
         .collect::<Vec<_>>();
 
+        // Each face maps the whole texture
+        let uvs = iter::repeat([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]])
+            .take(6)
+            .flatten()
+            .map(vertex::TextureCoordinates::from_f32)
+            .collect::<Vec<_>>();
+
         let indices = vec![
             0u16, 1, 2, 2, 3, 0, // top
             4, 5, 6, 6, 7, 4, // bottom
@@ -89,7 +97,12 @@ pub(super) fn cuboid<V: Into<Vec3>>(vertex_types: vertex::VertexTypes, dimension
         Primitive {
             radius,
             positions,
-            normals: Some(normals),
+            normals: vertex_types
+                .contains(vertex::VertexTypes::NORMAL)
+                .then_some(normals),
+            uvs: vertex_types
+                .contains(vertex::VertexTypes::UV)
+                .then_some(uvs),
             indices: Some(indices),
         }
     } else {
@@ -119,6 +132,7 @@ pub(super) fn cuboid<V: Into<Vec3>>(vertex_types: vertex::VertexTypes, dimension
             radius,
             positions,
             normals: None,
+            uvs: None,
             indices: Some(indices),
         }
     }
@@ -0,0 +1,57 @@
+use crate::math::geometry::{
+    vertex::{Normal, Position, TextureCoordinates},
+    Primitive,
+};
+
+/// Grids are indexed with u16, so each side is limited to 255 cells.
+const MAX_CELLS: u16 = 255;
+
+pub(super) fn grid(size: f32, columns: u16, rows: u16) -> Primitive {
+    if columns > MAX_CELLS || rows > MAX_CELLS {
+        log::warn!("Grid too dense, clamping to {0}x{0} cells", MAX_CELLS);
+        return grid(size, columns.min(MAX_CELLS), rows.min(MAX_CELLS));
+    }
+
+    let columns = columns.max(1);
+    let rows = rows.max(1);
+    let extent = size / 2.0;
+    let extent2 = extent.powf(2.0);
+    let radius = (extent2 + extent2).sqrt();
+
+    let vertex_count = (columns as usize + 1) * (rows as usize + 1);
+    let mut positions = Vec::with_capacity(vertex_count);
+    let mut normals = Vec::with_capacity(vertex_count);
+    let mut uvs = Vec::with_capacity(vertex_count);
+    for row in 0..=rows {
+        let v = row as f32 / rows as f32;
+        for column in 0..=columns {
+            let u = column as f32 / columns as f32;
+            positions.push(Position([-extent + size * u, 0.0, -extent + size * v]));
+            normals.push(Normal([0.0, 1.0, 0.0]));
+            uvs.push(TextureCoordinates::from_f32([u, 1.0 - v]));
+        }
+    }
+
+    // Same winding as the Plane primitive, facing up
+    let stride = columns + 1;
+    let mut indices = Vec::with_capacity(columns as usize * rows as usize * 6);
+    for row in 0..rows {
+        for column in 0..columns {
+            let near_left = row * stride + column;
+            let near_right = near_left + 1;
+            let far_left = near_left + stride;
+            let far_right = far_left + 1;
+
+            indices.extend_from_slice(&[near_right, far_left, far_right]);
+            indices.extend_from_slice(&[near_right, near_left, far_left]);
+        }
+    }
+
+    Primitive {
+        radius,
+        positions,
+        normals: Some(normals),
+        uvs: Some(uvs),
+        indices: Some(indices),
+    }
+}
@@ -1,4 +1,5 @@
 mod cuboid;
+mod grid;
 mod plane;
 mod sphere;
 
@@ -1,5 +1,5 @@
 use crate::math::geometry::{
-    vertex::{Normal, Position},
+    vertex::{Normal, Position, TextureCoordinates},
     Primitive,
 };
 
@@ -19,17 +19,18 @@ pub(super) fn plane(size: f32) -> Primitive {
 
     let mut positions = Vec::new();
     let mut normals = Vec::new();
-    // let mut uvs = Vec::new();
-    for (position, normal, _uv) in vertices.iter() {
+    let mut uvs = Vec::new();
+    for (position, normal, uv) in vertices.iter() {
         positions.push(Position(*position));
         normals.push(Normal(*normal));
-        // uvs.push(*uv);
+        uvs.push(TextureCoordinates::from_f32(*uv));
     }
 
     Primitive {
         radius,
         positions,
         normals: Some(normals),
+        uvs: Some(uvs),
         indices: Some(indices),
     }
 }
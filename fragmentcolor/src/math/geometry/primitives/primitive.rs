@@ -6,15 +6,37 @@ pub use crate::{
 
 type Error = Box<dyn std::error::Error>;
 
-pub(super) use primitives::{cuboid::*, plane::*, sphere::*};
+pub(super) use primitives::{cuboid::*, grid::*, plane::*, sphere::*};
 pub struct Primitive {
     pub positions: Vec<vertex::Position>,
     pub normals: Option<Vec<vertex::Normal>>,
+    pub(crate) uvs: Option<Vec<vertex::TextureCoordinates>>,
     pub indices: Option<Vec<u16>>,
     pub radius: f32,
 }
 
 impl Primitive {
+    /// Texture coordinates of each vertex, if any.
+    ///
+    /// All built-in primitives have them. Spheres wrap an equirectangular
+    /// texture around them, and Shapes map their bounding box to the texture.
+    pub fn uvs(&self) -> Option<&[vertex::TextureCoordinates]> {
+        self.uvs.as_deref()
+    }
+
+    /// Sets the texture coordinates of each vertex.
+    pub fn set_uvs(&mut self, uvs: Vec<vertex::TextureCoordinates>) -> &mut Self {
+        if uvs.len() != self.positions.len() {
+            log::warn!(
+                "Primitive has {} positions, but {} texture coordinates",
+                self.positions.len(),
+                uvs.len()
+            );
+        }
+        self.uvs = Some(uvs);
+        self
+    }
+
     pub fn create_mesh(&self) -> Result<mesh::BuiltMesh, Error> {
         let mut mesh_builder = mesh::MeshBuilder::new();
 
@@ -23,6 +45,9 @@ impl Primitive {
         if let Some(ref normals) = self.normals {
             mesh_builder.vertex(normals);
         }
+        if let Some(ref uvs) = self.uvs {
+            mesh_builder.vertex(uvs);
+        }
         if let Some(ref indices) = self.indices {
             mesh_builder.index(indices);
         }
@@ -30,19 +55,123 @@ impl Primitive {
         mesh_builder.build()
     }
 
+    /// Computes smooth per-vertex normals from the triangle faces.
+    ///
+    /// Each face normal is added to the vertices it touches, weighted by
+    /// the face area, then normalized. Vertices shared between faces get
+    /// averaged normals, so use separate vertices per face for flat shading.
+    ///
+    /// Requires an indexed triangle list. Otherwise this does nothing.
+    pub fn compute_normals(&mut self) -> &mut Self {
+        let indices = if let Some(ref indices) = self.indices {
+            indices
+        } else {
+            log::warn!("Cannot compute normals for a Primitive without indices");
+            return self;
+        };
+
+        let vertex_count = self.positions.len();
+        if indices.len() % 3 != 0 || indices.iter().any(|i| *i as usize >= vertex_count) {
+            log::warn!("Cannot compute normals: indices are not a valid triangle list");
+            return self;
+        }
+
+        let mut normals = vec![glam::Vec3::ZERO; vertex_count];
+        for face in indices.chunks_exact(3) {
+            let [a, b, c] = [face[0] as usize, face[1] as usize, face[2] as usize];
+            let position = |i: usize| glam::Vec3::from_array(self.positions[i].0);
+            let normal = (position(b) - position(a)).cross(position(c) - position(a));
+
+            normals[a] += normal;
+            normals[b] += normal;
+            normals[c] += normal;
+        }
+
+        self.normals = Some(
+            normals
+                .into_iter()
+                .map(|normal| vertex::Normal(normal.normalize_or_zero().into()))
+                .collect(),
+        );
+
+        self
+    }
+
     pub fn cube(size: f32) -> Self {
         primitives::cube(size)
     }
 
     pub fn cuboid<V: Into<Vec3>>(dimensions: V) -> Self {
-        primitives::cuboid(vertex::VertexTypes::empty(), dimensions)
+        primitives::cuboid(vertex::VertexTypes::UV, dimensions)
     }
 
     pub fn plane(size: f32) -> Self {
         primitives::plane(size)
     }
 
+    /// A flat plane subdivided in `columns` x `rows` cells
+    pub fn grid(size: f32, columns: u16, rows: u16) -> Self {
+        primitives::grid(size, columns, rows)
+    }
+
     pub fn sphere(radius: f32, detail: usize) -> Self {
-        primitives::sphere(vertex::VertexTypes::empty(), radius, detail)
+        primitives::sphere(vertex::VertexTypes::UV, radius, detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_cube_face_normals() {
+        let mut cube = primitives::cuboid(vertex::VertexTypes::NORMAL, [1.0, 1.0, 1.0]);
+        let expected = cube.normals.take().unwrap();
+
+        cube.compute_normals();
+
+        let computed = cube.normals.unwrap();
+        assert_eq!(computed.len(), expected.len());
+        for (computed, expected) in computed.iter().zip(expected.iter()) {
+            assert_eq!(computed.0, expected.0);
+        }
+    }
+
+    #[test]
+    fn built_in_primitives_have_uvs() {
+        let primitives = [
+            Primitive::cube(1.0),
+            Primitive::cuboid([1.0, 2.0, 3.0]),
+            Primitive::plane(1.0),
+            Primitive::grid(1.0, 2, 2),
+            Primitive::sphere(1.0, 2),
+        ];
+
+        for primitive in primitives.iter() {
+            let uvs = primitive.uvs().expect("Primitive without uvs");
+            assert_eq!(uvs.len(), primitive.positions.len());
+        }
+    }
+
+    #[test]
+    fn cube_faces_cover_the_whole_texture() {
+        let cube = Primitive::cube(1.0);
+        let uvs = cube.uvs().unwrap();
+
+        for face in uvs.chunks_exact(4) {
+            let corners = [[0, 0], [u16::MAX, 0], [u16::MAX, u16::MAX], [0, u16::MAX]];
+            assert_eq!(face.iter().map(|uv| uv.0).collect::<Vec<_>>(), corners);
+        }
+    }
+
+    #[test]
+    fn skips_normals_without_indices() {
+        let mut plane = Primitive::plane(1.0);
+        plane.indices = None;
+        plane.normals = None;
+
+        plane.compute_normals();
+
+        assert!(plane.normals.is_none());
     }
 }
@@ -98,6 +98,11 @@ pub(super) fn sphere(vertex_types: vertex::VertexTypes, radius: f32, detail: usi
     } else {
         None
     };
+    let mut uvs = if vertex_types.contains(vertex::VertexTypes::UV) {
+        Some(Vec::with_capacity(vertices.len()))
+    } else {
+        None
+    };
 
     for v in vertices {
         let n = v.normalize();
@@ -105,12 +110,26 @@ pub(super) fn sphere(vertex_types: vertex::VertexTypes, radius: f32, detail: usi
         if let Some(ref mut normals) = normals {
             normals.push(vertex::Normal(n.into()));
         }
+        if let Some(ref mut uvs) = uvs {
+            uvs.push(equirectangular_uv(n));
+        }
     }
 
     Primitive {
         positions,
         normals,
+        uvs,
         radius,
         indices: Some(indices),
     }
 }
+
+/// Maps a direction to the texture coordinates of an equirectangular texture.
+///
+/// Vertices are shared between faces, so the triangles crossing
+/// the seam at u = 0 interpolate over the whole texture.
+fn equirectangular_uv(direction: glam::Vec3) -> vertex::TextureCoordinates {
+    let u = 0.5 + direction.z.atan2(direction.x) / std::f32::consts::TAU;
+    let v = 0.5 - direction.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+    vertex::TextureCoordinates::from_f32([u, v])
+}
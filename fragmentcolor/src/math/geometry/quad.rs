@@ -206,6 +206,30 @@ impl Quad {
             && self.max_y >= other.min_y
     }
 
+    /// Returns the overlapping region of both Quads, or None if they don't overlap.
+    ///
+    /// Like viewports and scissor rects, the max edges are exclusive,
+    /// so Quads that only touch each other don't intersect.
+    pub fn intersect(&self, other: Quad) -> Option<Quad> {
+        let intersection = Quad {
+            min_x: self.min_x.max(other.min_x),
+            min_y: self.min_y.max(other.min_y),
+            max_x: self.max_x.min(other.max_x),
+            max_y: self.max_y.min(other.max_y),
+        };
+
+        if intersection.min_x < intersection.max_x && intersection.min_y < intersection.max_y {
+            Some(intersection)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the pixel at (x, y) is inside this Quad.
+    pub fn contains_point(&self, x: u32, y: u32) -> bool {
+        x >= self.min_x && x < self.max_x && y >= self.min_y && y < self.max_y
+    }
+
     pub fn area(&self) -> u32 {
         self.width() * self.height()
     }
@@ -295,6 +319,39 @@ impl Quad {
         self.width() as f32 / self.height() as f32
     }
 
+    /// Scales the size of this Quad by `factor`, keeping its origin.
+    pub fn scale(&self, factor: f32) -> Quad {
+        let factor = factor.max(0.0);
+
+        Quad::from_region(
+            self.min_x,
+            self.min_y,
+            (self.width_f32() * factor).round() as u32,
+            (self.height_f32() * factor).round() as u32,
+        )
+    }
+
+    /// Returns the largest Quad with this aspect ratio that fits inside
+    /// `bounds`, centered on it (a "contain" fit, with letterboxing).
+    pub fn fit_within(&self, bounds: Quad) -> Quad {
+        if self.width() == 0 || self.height() == 0 {
+            return Quad::from_region(bounds.min_x, bounds.min_y, 0, 0);
+        }
+
+        let factor =
+            (bounds.width_f32() / self.width_f32()).min(bounds.height_f32() / self.height_f32());
+
+        let width = ((self.width_f32() * factor).round() as u32).min(bounds.width());
+        let height = ((self.height_f32() * factor).round() as u32).min(bounds.height());
+
+        Quad::from_region(
+            bounds.min_x + (bounds.width() - width) / 2,
+            bounds.min_y + (bounds.height() - height) / 2,
+            width,
+            height,
+        )
+    }
+
     pub fn pixel_center(&self) -> (u32, u32) {
         (
             self.min_x + self.half_width(),
@@ -438,6 +495,73 @@ fn translate_region(
 mod tests {
     use super::Quad;
 
+    #[test]
+    fn intersect_overlapping_touching_and_disjoint() {
+        let a = Quad::from_region(0, 0, 10, 10);
+
+        let overlapping = Quad::from_region(5, 5, 10, 10);
+        assert_eq!(
+            a.intersect(overlapping),
+            Some(Quad::from_region(5, 5, 5, 5))
+        );
+        assert_eq!(overlapping.intersect(a), a.intersect(overlapping));
+
+        let inside = Quad::from_region(2, 3, 4, 5);
+        assert_eq!(a.intersect(inside), Some(inside));
+
+        let touching = Quad::from_region(10, 0, 10, 10);
+        assert_eq!(a.intersect(touching), None);
+
+        let disjoint = Quad::from_region(20, 20, 5, 5);
+        assert_eq!(a.intersect(disjoint), None);
+
+        let mut union = a;
+        union.union(disjoint);
+        assert_eq!(union, Quad::from_region(0, 0, 25, 25));
+    }
+
+    #[test]
+    fn scale_keeps_origin() {
+        let quad = Quad::from_region(10, 20, 100, 50);
+
+        assert_eq!(quad.scale(2.0), Quad::from_region(10, 20, 200, 100));
+        assert_eq!(quad.scale(0.5), Quad::from_region(10, 20, 50, 25));
+        assert_eq!(quad.scale(-1.0), Quad::from_region(10, 20, 0, 0));
+    }
+
+    #[test]
+    fn fit_landscape_within_portrait() {
+        let landscape = Quad::from_size(1920, 1080);
+        let portrait = Quad::from_size(1080, 1920);
+
+        // letterboxed: full width, centered vertically
+        let fit = landscape.fit_within(portrait);
+        assert_eq!(fit, Quad::from_region(0, 656, 1080, 608));
+        assert!((fit.aspect() - landscape.aspect()).abs() < 0.01);
+
+        // pillarboxed: full height, centered horizontally
+        let fit = portrait.fit_within(landscape);
+        assert_eq!(fit, Quad::from_region(656, 0, 608, 1080));
+
+        assert_eq!(
+            Quad::from_size(0, 10).fit_within(portrait),
+            Quad::from_size(0, 0)
+        );
+        assert_eq!(Quad::from_size(0, 10).aspect(), 0.0);
+        assert_eq!(Quad::from_size(10, 0).aspect(), 0.0);
+    }
+
+    #[test]
+    fn contains_point() {
+        let quad = Quad::from_region(10, 10, 10, 10);
+
+        assert!(quad.contains_point(10, 10));
+        assert!(quad.contains_point(19, 19));
+        assert!(!quad.contains_point(20, 19));
+        assert!(!quad.contains_point(19, 20));
+        assert!(!quad.contains_point(9, 15));
+    }
+
     #[test]
     fn clamp_with_intersection() {
         fn test(
@@ -16,6 +16,31 @@ fn stroke_position(vertex: lyon::tessellation::StrokeVertex) -> vertex::Position
     vertex::Position([p.x, p.y, 0.0])
 }
 
+/// Maps the bounding box of the vertices to the whole texture.
+fn bounding_box_uvs(positions: &[vertex::Position]) -> Vec<vertex::TextureCoordinates> {
+    let (min, max) = positions.iter().fold(
+        ([f32::MAX, f32::MAX], [f32::MIN, f32::MIN]),
+        |(min, max), vertex::Position([x, y, _])| {
+            (
+                [min[0].min(*x), min[1].min(*y)],
+                [max[0].max(*x), max[1].max(*y)],
+            )
+        },
+    );
+    let width = (max[0] - min[0]).max(f32::EPSILON);
+    let height = (max[1] - min[1]).max(f32::EPSILON);
+
+    positions
+        .iter()
+        .map(|vertex::Position([x, y, _])| {
+            vertex::TextureCoordinates::from_f32([
+                (x - min[0]) / width,
+                1.0 - (y - min[1]) / height,
+            ])
+        })
+        .collect()
+}
+
 fn bounding_radius(path: &lyon::path::Path) -> f32 {
     path.iter().fold(0.0, |accum, item| {
         let p = item.from();
@@ -35,6 +60,7 @@ impl ShapeBuilder {
         let radius = bounding_radius(path);
 
         Primitive {
+            uvs: Some(bounding_box_uvs(&buffer.vertices)),
             positions: buffer.vertices,
             indices: Some(buffer.indices),
             normals: None,
@@ -51,6 +77,7 @@ impl ShapeBuilder {
         let radius = bounding_radius(path);
 
         Primitive {
+            uvs: Some(bounding_box_uvs(&buffer.vertices)),
             positions: buffer.vertices,
             indices: Some(buffer.indices),
             normals: None,
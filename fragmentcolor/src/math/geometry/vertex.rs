@@ -44,6 +44,12 @@ impl Normal {
 pub struct TextureCoordinates(pub [u16; 2]);
 
 impl TextureCoordinates {
+    /// Packs normalized coordinates in the 0..1 range
+    pub fn from_f32(uv: [f32; 2]) -> Self {
+        let pack = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16;
+        Self([pack(uv[0]), pack(uv[1])])
+    }
+
     pub(crate) const fn layout<const LOCATION: u32>() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: mem::size_of::<Self>() as u64,
@@ -61,5 +67,6 @@ bitflags::bitflags!(
     /// Optional vertex types.
     pub struct VertexTypes: u32 {
         const NORMAL = 1 << 1;
+        const UV = 1 << 2;
     }
 );
@@ -0,0 +1,43 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Errors that stop a frame from being rendered.
+#[derive(Debug)]
+pub enum RendererError {
+    /// The frame of a Render Target could not be acquired.
+    Surface(wgpu::SurfaceError),
+
+    /// The GPU reported an error while rendering, like a validation error.
+    ///
+    /// Only captured on native. On the Web, these errors are sent to
+    /// the `FragmentColor::on_gpu_error` callback instead.
+    Gpu(wgpu::Error),
+
+    /// The Renderer or one of its databases is locked by another thread.
+    Locked,
+}
+
+impl Display for RendererError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Surface(error) => write!(f, "Cannot acquire the next frame: {}", error),
+            Self::Gpu(error) => write!(f, "GPU error while rendering: {}", error),
+            Self::Locked => write!(f, "The Renderer is locked by another thread"),
+        }
+    }
+}
+
+impl std::error::Error for RendererError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Surface(error) => Some(error),
+            Self::Gpu(error) => Some(error),
+            Self::Locked => None,
+        }
+    }
+}
+
+impl From<wgpu::SurfaceError> for RendererError {
+    fn from(error: wgpu::SurfaceError) -> Self {
+        Self::Surface(error)
+    }
+}
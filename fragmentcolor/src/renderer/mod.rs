@@ -1,10 +1,14 @@
+mod error;
 mod limits;
 pub mod options;
 pub(crate) mod renderer;
 pub(crate) mod renderpass;
 pub mod target;
+mod timing;
 
+pub use error::*;
 pub use options::*;
 pub(crate) use renderer::*;
 pub(super) use renderpass::*;
 pub use target::*;
+pub(crate) use timing::*;
@@ -53,15 +53,241 @@ pub static DEVICE_LIMITS: phf::Map<&str, wgpu::Limits> = phf_map! {
     "default" => DEFAULT_LIMITS,
 };
 
+/// Convenience Lookup Table for converting a static string
+/// from the external API into a the wgpu::Backends bitflags.
+///
+/// Multiple backends can be combined with commas, i.e. "vulkan,gl".
+pub static BACKENDS: phf::Map<&str, wgpu::Backends> = phf_map! {
+    "vulkan" => wgpu::Backends::VULKAN,
+    "metal" => wgpu::Backends::METAL,
+    "dx12" => wgpu::Backends::DX12,
+    "dx11" => wgpu::Backends::DX11,
+    "gl" => wgpu::Backends::GL,
+    "opengl" => wgpu::Backends::GL,
+    "webgl" => wgpu::Backends::GL,
+    "webgpu" => wgpu::Backends::BROWSER_WEBGPU,
+
+    // Vulkan, Metal, DX12 and WebGPU
+    "primary" => wgpu::Backends::PRIMARY,
+    // OpenGL and DX11
+    "secondary" => wgpu::Backends::SECONDARY,
+
+    "all" => wgpu::Backends::all(),
+    "default" => wgpu::Backends::all(),
+};
+
+/// Convenience Lookup Table for converting a static string
+/// from the external API into a wgpu::PresentMode.
+///
+/// "default" keeps the first mode the surface supports.
+pub static PRESENT_MODES: phf::Map<&str, wgpu::PresentMode> = phf_map! {
+    // Waits for the display refresh. Supported everywhere.
+    "vsync" => wgpu::PresentMode::Fifo,
+    "fifo" => wgpu::PresentMode::Fifo,
+    "fifo_relaxed" => wgpu::PresentMode::FifoRelaxed,
+
+    // Presents right away, which may tear. Useful for benchmarks.
+    "no_vsync" => wgpu::PresentMode::Immediate,
+    "immediate" => wgpu::PresentMode::Immediate,
+
+    // Low latency without tearing, replacing the queued frame.
+    "mailbox" => wgpu::PresentMode::Mailbox,
+
+    // Let wgpu choose the best supported mode
+    "auto_vsync" => wgpu::PresentMode::AutoVsync,
+    "auto_no_vsync" => wgpu::PresentMode::AutoNoVsync,
+};
+
+/// Convenience Lookup Table for converting a static string
+/// from the external API into a wgpu::CompositeAlphaMode.
+///
+/// "default" uses PreMultiplied if the surface supports it.
+///
+/// Platform support varies:
+/// - Vulkan exposes the modes of the compositor, usually
+///   Opaque and PreMultiplied (or Inherit on X11/Wayland).
+/// - Metal supports Opaque and PostMultiplied.
+/// - DX12 supports Opaque only when rendering to a window.
+/// - WebGPU supports Opaque and PreMultiplied.
+/// - WebGL supports Opaque and PreMultiplied.
+pub static ALPHA_MODES: phf::Map<&str, wgpu::CompositeAlphaMode> = phf_map! {
+    // Let wgpu choose between Opaque and Inherit
+    "auto" => wgpu::CompositeAlphaMode::Auto,
+
+    // Alpha is ignored, the window is never transparent.
+    "opaque" => wgpu::CompositeAlphaMode::Opaque,
+
+    // The compositor blends the colors as premultiplied by alpha.
+    // Transparent clear colors let the desktop show through.
+    "premultiplied" => wgpu::CompositeAlphaMode::PreMultiplied,
+
+    // The compositor multiplies the colors by alpha itself.
+    "postmultiplied" => wgpu::CompositeAlphaMode::PostMultiplied,
+
+    // Uses whatever the platform's native window sets.
+    "inherit" => wgpu::CompositeAlphaMode::Inherit,
+};
+
+/// Additive blending: adds the source color to the destination,
+/// weighted by the source alpha. Useful for particles and glows.
+pub(crate) const ADDITIVE_BLENDING: wgpu::BlendState = wgpu::BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+};
+
+/// Multiply blending: multiplies the source color by the destination.
+/// Transparent source pixels leave the destination untouched.
+pub(crate) const MULTIPLY_BLENDING: wgpu::BlendState = wgpu::BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Dst,
+        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent::OVER,
+};
+
+/// How the colors drawn by a RenderPass are combined with the target.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Overwrites the destination. Alpha is ignored.
+    Replace,
+    /// Classic "source over" blending for straight (non-premultiplied) alpha.
+    Alpha,
+    /// "Source over" blending for colors with premultiplied alpha.
+    Premultiplied,
+    /// Adds the source color to the destination. Useful for particles and glows.
+    Additive,
+    /// Multiplies the source color by the destination.
+    Multiply,
+    /// Any blend state wgpu supports. Can't be serialized.
+    #[serde(skip)]
+    Custom {
+        color: wgpu::BlendComponent,
+        alpha: wgpu::BlendComponent,
+    },
+}
+
+impl From<BlendMode> for wgpu::BlendState {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::Replace => wgpu::BlendState::REPLACE,
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => ADDITIVE_BLENDING,
+            BlendMode::Multiply => MULTIPLY_BLENDING,
+            BlendMode::Custom { color, alpha } => wgpu::BlendState { color, alpha },
+        }
+    }
+}
+
+/// How the 3D RenderPasses assemble and cull the triangles of meshes.
+///
+/// The 2D Toy RenderPass always draws quads as triangle strips.
+/// Line and strip topologies still read the mesh index buffers,
+/// so the indices must describe lines or strips accordingly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrimitiveOptions {
+    pub topology: wgpu::PrimitiveTopology,
+    pub front_face: wgpu::FrontFace,
+    pub cull_mode: Option<wgpu::Face>, // None draws both faces
+}
+
+impl Default for PrimitiveOptions {
+    fn default() -> Self {
+        Self {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: Some(wgpu::Face::Back),
+        }
+    }
+}
+
+impl From<PrimitiveOptions> for wgpu::PrimitiveState {
+    fn from(options: PrimitiveOptions) -> Self {
+        Self {
+            topology: options.topology,
+            front_face: options.front_face,
+            cull_mode: options.cull_mode,
+            ..Default::default()
+        }
+    }
+}
+
+/// Depth test of the 3D RenderPasses.
+///
+/// Each target keeps its depth buffer between renders, so a render with
+/// `clear: None` tests against the depth written by the previous render.
+/// The first render to a target, or after it's resized, always clears it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DepthOptions {
+    pub compare: wgpu::CompareFunction,
+    pub write_enabled: bool,
+    pub clear: Option<f32>, // None loads the previous depth
+}
+
+impl Default for DepthOptions {
+    fn default() -> Self {
+        Self {
+            compare: wgpu::CompareFunction::LessEqual,
+            write_enabled: true,
+            clear: Some(1.0),
+        }
+    }
+}
+
+impl DepthOptions {
+    pub(crate) fn state(&self, format: wgpu::TextureFormat) -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            format,
+            depth_compare: self.compare,
+            depth_write_enabled: self.write_enabled,
+            bias: Default::default(),
+            stencil: Default::default(),
+        }
+    }
+
+    /// Loads the previous depth if the target asks for it or nothing clears it.
+    ///
+    /// Clears a new depth buffer, as it has no previous depth to load.
+    pub(crate) fn load_op(&self, new_buffer: bool, load_depth: bool) -> wgpu::LoadOp<f32> {
+        if new_buffer {
+            return wgpu::LoadOp::Clear(self.clear.unwrap_or(1.0));
+        }
+
+        match self.clear {
+            Some(depth) if !load_depth => wgpu::LoadOp::Clear(depth),
+            _ => wgpu::LoadOp::Load,
+        }
+    }
+}
+
 #[cfg_attr(wasm, wasm_bindgen(getter_with_clone))]
 /// Options for configuring the Renderer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RendererOptions {
     pub force_software_rendering: bool,
     pub power_preference: String,
+    pub backends: String, // see BACKENDS; empty uses all available backends
     pub panic_on_error: bool,
     pub device_limits: String,
     pub render_pass: String, // supports only ("flat" or "solid") for now, not chainable yet
+    #[cfg_attr(wasm, wasm_bindgen(skip))]
+    pub blend_mode: Option<BlendMode>, // None uses the RenderPass default
+    #[serde(skip)]
+    #[cfg_attr(wasm, wasm_bindgen(skip))]
+    pub primitive: PrimitiveOptions, // used by 3D RenderPasses only
+    #[serde(skip)]
+    #[cfg_attr(wasm, wasm_bindgen(skip))]
+    pub depth: DepthOptions, // used by 3D RenderPasses only
+    pub debug_labels: bool,  // groups commands by pass and target in GPU debuggers
 }
 
 impl Default for RendererOptions {
@@ -69,9 +295,131 @@ impl Default for RendererOptions {
         Self {
             force_software_rendering: false,
             power_preference: "default".to_string(),
+            backends: "default".to_string(),
             panic_on_error: false,
             device_limits: "default".to_string(),
             render_pass: DEFAULT_RENDER_PASS.to_string(),
+            blend_mode: None,
+            primitive: PrimitiveOptions::default(),
+            depth: DepthOptions::default(),
+            debug_labels: false,
         }
     }
 }
+
+/// Tone mapping operators, used to display HDR colors on LDR targets.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ToneMapping {
+    /// Keeps the colors. LDR targets clip everything brighter than 1.0.
+    None,
+    /// Compresses the colors with `color / (1.0 + color)`.
+    Reinhard,
+    /// The ACES filmic curve, fitted by Krzysztof Narkowicz.
+    Aces,
+}
+
+/// Options for `Texture::tonemap`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ToneMapOptions {
+    pub operator: ToneMapping,
+    pub exposure: f32, // multiplies the colors before the operator is applied
+}
+
+impl Default for ToneMapOptions {
+    fn default() -> Self {
+        Self {
+            operator: ToneMapping::None,
+            exposure: 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_modes_convert_to_blend_states() {
+        assert_eq!(
+            wgpu::BlendState::from(BlendMode::Replace),
+            wgpu::BlendState::REPLACE
+        );
+        assert_eq!(
+            wgpu::BlendState::from(BlendMode::Additive),
+            ADDITIVE_BLENDING
+        );
+
+        let custom = BlendMode::Custom {
+            color: wgpu::BlendComponent::REPLACE,
+            alpha: wgpu::BlendComponent::OVER,
+        };
+        assert_eq!(
+            wgpu::BlendState::from(custom),
+            wgpu::BlendState {
+                color: wgpu::BlendComponent::REPLACE,
+                alpha: wgpu::BlendComponent::OVER,
+            }
+        );
+    }
+
+    #[test]
+    fn primitive_options_reach_the_primitive_state() {
+        let options = PrimitiveOptions {
+            topology: wgpu::PrimitiveTopology::LineList,
+            front_face: wgpu::FrontFace::Cw,
+            cull_mode: None,
+        };
+        let state = wgpu::PrimitiveState::from(options);
+
+        assert_eq!(state.topology, wgpu::PrimitiveTopology::LineList);
+        assert_eq!(state.front_face, wgpu::FrontFace::Cw);
+        assert_eq!(state.cull_mode, None);
+        assert_eq!(state.strip_index_format, None);
+    }
+
+    #[test]
+    fn depth_options_reach_the_depth_state() {
+        let options = DepthOptions {
+            compare: wgpu::CompareFunction::Always,
+            write_enabled: false,
+            clear: None,
+        };
+        let state = options.state(wgpu::TextureFormat::Depth24Plus);
+
+        assert_eq!(state.depth_compare, wgpu::CompareFunction::Always);
+        assert!(!state.depth_write_enabled);
+        assert_eq!(state.format, wgpu::TextureFormat::Depth24Plus);
+    }
+
+    #[test]
+    fn depth_is_only_loaded_from_existing_buffers() {
+        let load = DepthOptions {
+            clear: None,
+            ..Default::default()
+        };
+        assert_eq!(load.load_op(false, false), wgpu::LoadOp::Load);
+        assert_eq!(load.load_op(true, false), wgpu::LoadOp::Clear(1.0));
+
+        let clear = DepthOptions {
+            clear: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(clear.load_op(false, false), wgpu::LoadOp::Clear(0.5));
+        assert_eq!(clear.load_op(true, false), wgpu::LoadOp::Clear(0.5));
+    }
+
+    #[test]
+    fn targets_can_load_depth_over_the_global_clear() {
+        let clear = DepthOptions::default();
+        assert_eq!(clear.load_op(false, true), wgpu::LoadOp::Load);
+        assert_eq!(clear.load_op(true, true), wgpu::LoadOp::Clear(1.0));
+    }
+
+    #[test]
+    fn default_primitive_options_cull_back_faces() {
+        let state = wgpu::PrimitiveState::from(PrimitiveOptions::default());
+
+        assert_eq!(state.topology, wgpu::PrimitiveTopology::TriangleList);
+        assert_eq!(state.cull_mode, Some(wgpu::Face::Back));
+    }
+}
@@ -2,15 +2,20 @@ use wgpu::util::DeviceExt;
 
 use crate::{
     app::window::IsWindow,
+    components::Color,
+    math::geometry::Quad,
     renderer::{
-        options::{DEVICE_LIMITS, POWER_PREFERENCE},
+        options::{ALPHA_MODES, BACKENDS, DEVICE_LIMITS, POWER_PREFERENCE, PRESENT_MODES},
+        renderpass::{Blit, BlitPipelines, DEPTH_FORMAT},
         target::{
-            RenderTarget, RenderTargetCollection, RenderTargets, TargetId, TextureTarget,
-            WindowTarget,
+            Dimensions, IsRenderTarget, RenderTarget, RenderTargetCollection, RenderTargets,
+            TargetId, TextureTarget, WindowTarget,
         },
-        RenderPass, RendererOptions,
+        DepthOptions, FrameTimer, PrimitiveOptions, RenderPass, RenderPassResult, RenderedFrames,
+        RendererError, RendererOptions, TimedPass, ToneMapOptions,
     },
     resources::{
+        buffer::Readback,
         mesh::{MeshData, MeshId},
         texture::{Texture, TextureId},
         Resources,
@@ -18,12 +23,26 @@ use crate::{
     sampler::{create_sampler, SamplerOptions},
     scene::Scene,
 };
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use fxhash::FxHashMap;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
 
 pub type Commands = Vec<wgpu::CommandBuffer>;
 
 type Error = Box<dyn std::error::Error>;
 
+/// Optional features requested when the adapter supports them.
+const COMPRESSED_TEXTURE_FEATURES: wgpu::Features = wgpu::Features::TEXTURE_COMPRESSION_BC
+    .union(wgpu::Features::TEXTURE_COMPRESSION_ETC2)
+    .union(wgpu::Features::TEXTURE_COMPRESSION_ASTC);
+const TIMING_FEATURES: wgpu::Features = wgpu::Features::TIMESTAMP_QUERY;
+const PUSH_CONSTANT_FEATURES: wgpu::Features = wgpu::Features::PUSH_CONSTANTS;
+
+/// Color of the texture drawn in place of missing textures.
+const DEFAULT_PLACEHOLDER_COLOR: Color = Color(0xFF00FFFF);
+
 pub(crate) trait RenderContext {
     fn read_resources(&self) -> Result<RwLockReadGuard<Resources>, Error>;
     fn write_resources(&self) -> Result<RwLockWriteGuard<Resources>, Error>;
@@ -45,7 +64,23 @@ pub(crate) struct Renderer {
     resources: Arc<RwLock<Resources>>,
     targets: Arc<RwLock<RenderTargets>>,
     pixel: TextureId,
+    placeholder: TextureId,
     pass: String, // @TODO support multiple render passes
+    blend: Option<wgpu::BlendState>,
+    primitive: PrimitiveOptions,
+    depth: DepthOptions,
+    depth_buffers: RwLock<FxHashMap<TargetId, DepthBuffer>>,
+    timer: RwLock<Option<FrameTimer>>,
+    blit_pipelines: BlitPipelines,
+    panic_on_error: bool,
+    debug_labels: bool,
+}
+
+/// Depth buffer of a Render Target, kept between renders.
+#[derive(Debug)]
+struct DepthBuffer {
+    view: Arc<wgpu::TextureView>,
+    size: wgpu::Extent3d,
 }
 
 unsafe impl Sync for Renderer {}
@@ -125,12 +160,28 @@ impl Renderer {
         window: Option<&W>,
     ) -> Result<Renderer, Error> {
         let pass = options.render_pass.clone();
+        let blend = options.blend_mode.map(wgpu::BlendState::from);
+        let primitive = options.primitive;
+        let depth = options.depth;
+        let panic_on_error = options.panic_on_error;
+        let debug_labels = options.debug_labels;
         let (instance, adapter, device, queue, targets) =
             Internal::gpu_objects(options, window).await?;
         let targets = Arc::new(RwLock::new(targets));
 
         let mut resources = Resources::new();
-        let pixel = resources.add_texture(Internal::create_default_blank_pixel(&device, &queue)?);
+        let pixel = resources.add_texture(Internal::create_pixel_texture(
+            &device,
+            &queue,
+            "Default Blank Pixel",
+            Color(0xFFFFFFFF),
+        )?);
+        let placeholder = resources.add_texture(Internal::create_pixel_texture(
+            &device,
+            &queue,
+            "Placeholder Texture",
+            DEFAULT_PLACEHOLDER_COLOR,
+        )?);
         let resources = Arc::new(RwLock::new(resources));
 
         Ok(Renderer {
@@ -139,7 +190,16 @@ impl Renderer {
             device,
             queue,
             pass,
+            blend,
+            primitive,
+            depth,
+            depth_buffers: RwLock::new(FxHashMap::default()),
+            timer: RwLock::new(None),
+            blit_pipelines: BlitPipelines::new(&device),
+            panic_on_error,
+            debug_labels,
             pixel,
+            placeholder,
             targets,
             resources,
         })
@@ -150,6 +210,151 @@ impl Renderer {
         self.pixel
     }
 
+    /// Returns the texture drawn in place of missing textures.
+    pub(crate) fn placeholder_texture_id(&self) -> TextureId {
+        self.placeholder
+    }
+
+    /// Changes the color of the texture drawn in place of missing textures.
+    pub(crate) fn set_placeholder_texture(&self, color: Color) -> Result<(), Error> {
+        let resources = self.read_resources()?;
+        let placeholder = resources
+            .get_texture(&self.placeholder)
+            .ok_or("Placeholder texture not found")?;
+
+        self.queue.write_texture(
+            placeholder.data.as_image_copy(),
+            &color.0.to_be_bytes(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4),
+                rows_per_image: None,
+            },
+            placeholder.size,
+        );
+
+        Ok(())
+    }
+
+    /// Information about the GPU adapter used by this Renderer
+    pub(crate) fn adapter_info(&self) -> wgpu::AdapterInfo {
+        self.adapter.get_info()
+    }
+
+    /// Limits of the GPU device used by this Renderer
+    pub(crate) fn limits(&self) -> wgpu::Limits {
+        self.device.limits()
+    }
+
+    /// Returns the blend state configured by the user,
+    /// or the given RenderPass default if none was set.
+    pub(crate) fn blend_state(&self, default: wgpu::BlendState) -> wgpu::BlendState {
+        self.blend.unwrap_or(default)
+    }
+
+    /// Returns the depth buffer of a target, and whether it was just created.
+    ///
+    /// The buffer is recreated when the target is resized.
+    pub(crate) fn depth_buffer(
+        &self,
+        target_id: TargetId,
+        size: wgpu::Extent3d,
+    ) -> (Arc<wgpu::TextureView>, bool) {
+        let mut depth_buffers = if let Ok(depth_buffers) = self.depth_buffers.write() {
+            depth_buffers
+        } else {
+            log::warn!("Depth buffers lock poisoned. Creating a temporary depth buffer.");
+            return (
+                Arc::new(Internal::create_depth_view(&self.device, size)),
+                true,
+            );
+        };
+
+        if let Some(buffer) = depth_buffers.get(&target_id) {
+            if buffer.size == size {
+                return (buffer.view.clone(), false);
+            }
+        }
+
+        let view = Arc::new(Internal::create_depth_view(&self.device, size));
+        depth_buffers.insert(
+            target_id,
+            DepthBuffer {
+                view: view.clone(),
+                size,
+            },
+        );
+        (view, true)
+    }
+
+    /// Sends GPU errors, like validation or out of memory errors, to a callback.
+    ///
+    /// Errors are still logged, and still panic after the callback
+    /// runs if `panic_on_error` is set.
+    pub(crate) fn on_error(&self, callback: impl Fn(wgpu::Error) + Send + 'static) {
+        let panic_on_error = self.panic_on_error;
+        self.device.on_uncaptured_error(Box::new(move |error| {
+            log::error!("\n\n==== GPU error: ====\n\n{:#?}\n", error);
+            let message = error.to_string();
+            callback(error);
+            if panic_on_error {
+                panic!("GPU error: {}", message);
+            }
+        }));
+    }
+
+    /// Drives pending GPU work, like buffer mapping callbacks.
+    ///
+    /// Returns true if the queue is empty.
+    pub(crate) fn poll(&self, maintain: wgpu::Maintain) -> bool {
+        self.device.poll(maintain).is_queue_empty()
+    }
+
+    /// Starts or stops measuring how long each RenderPass takes on the GPU.
+    ///
+    /// Returns false if the device does not support timestamp queries,
+    /// in which case timing stays disabled.
+    pub(crate) fn enable_timing(&self, enabled: bool) -> bool {
+        let mut timer = if let Ok(timer) = self.timer.write() {
+            timer
+        } else {
+            return false;
+        };
+
+        if !enabled {
+            *timer = None;
+            return false;
+        }
+
+        if timer.is_none() {
+            *timer = FrameTimer::new(&self.device, &self.queue);
+        }
+        timer.is_some()
+    }
+
+    /// GPU time of each RenderPass of the last timed frame, in milliseconds.
+    ///
+    /// Empty if timing is disabled or not supported.
+    pub(crate) fn last_frame_timings(&self) -> Vec<(String, f64)> {
+        match self.timer.read().as_deref() {
+            Ok(Some(timer)) => timer.last_frame_timings(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Reserves the timestamps of a RenderPass if timing is enabled.
+    pub(crate) fn time_pass(&self, label: std::fmt::Arguments) -> Option<TimedPass> {
+        match self.timer.read().as_deref() {
+            Ok(Some(timer)) => timer.time_pass(label.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Whether passes should group their commands for GPU debuggers
+    pub(crate) fn debug_labels(&self) -> bool {
+        self.debug_labels
+    }
+
     /// Registers a loaded mesh to the Resources Manager.
     ///
     /// This function takes a MeshData instance generated by the MeshBuilder
@@ -185,8 +390,13 @@ impl Renderer {
     }
 
     /// Removes a texture from the Resources Manager.
-    #[allow(dead_code)]
+    ///
+    /// The built-in default and placeholder textures can't be removed.
     pub(crate) fn remove_texture(&self, id: &TextureId) -> Result<Option<Texture>, Error> {
+        if *id == self.default_pixel_id() || *id == self.placeholder_texture_id() {
+            return Err("Cannot remove a built-in texture!".into());
+        }
+
         if let Ok(mut resources) = self.write_resources() {
             Ok(resources.remove_texture(id))
         } else {
@@ -194,6 +404,25 @@ impl Renderer {
         }
     }
 
+    /// Reads a loaded texture, or the texture behind a Texture target.
+    pub(crate) fn inspect_texture<T>(
+        &self,
+        id: &TextureId,
+        read: impl FnOnce(&Texture) -> T,
+    ) -> Option<T> {
+        let resources = self.read_resources().ok()?;
+        let targets = self.read_targets().ok()?;
+
+        Internal::find_texture(&resources, &targets, id).map(read)
+    }
+
+    /// How many textures the Resources Manager holds
+    pub(crate) fn texture_count(&self) -> usize {
+        self.read_resources()
+            .map(|resources| resources.texture_count())
+            .unwrap_or(0)
+    }
+
     /// Registers an OS Window or a Web Canvas element as a rendering target.
     ///
     /// This method expects the Window to implement the `IsWindow` trait,
@@ -211,6 +440,27 @@ impl Renderer {
         }
     }
 
+    /// Updates the scale factor of a Window target after the OS changes it
+    pub(crate) fn rescale_target(&self, id: &TargetId, scaling: f32) -> Result<(), Error> {
+        let mut targets = self.write_targets()?;
+        match targets.get_mut(id) {
+            Some(RenderTarget::Window(target)) => {
+                target.scaling_factor = scaling;
+                Ok(())
+            }
+            _ => Err(format!("Window Target {:?} not found!", id).into()),
+        }
+    }
+
+    /// The surface configuration of a Window target
+    pub(crate) fn surface_config(&self, id: &TargetId) -> Option<wgpu::SurfaceConfiguration> {
+        let targets = self.read_targets().ok()?;
+        match targets.get(id) {
+            Some(RenderTarget::Window(target)) => Some(target.config.clone()),
+            _ => None,
+        }
+    }
+
     /// Registers a Texture as a rendering target.
     pub(crate) fn add_texture_target(&self, texture: Texture) -> Result<TargetId, Error> {
         let target = RenderTarget::Texture(TextureTarget::from_texture(self, texture)?);
@@ -233,8 +483,152 @@ impl Renderer {
         // }
     }
 
+    /// Copies the last rendered frame of a Texture target to a staging buffer.
+    ///
+    /// Read it with `Readback::read()` after releasing the Renderer.
+    pub(crate) fn target_frame_readback(&self, id: &TargetId) -> Result<Readback, Error> {
+        let targets = self.read_targets()?;
+        match targets.get(id) {
+            Some(RenderTarget::Texture(target)) => target.frame_readback(self),
+            Some(RenderTarget::Window(_)) => Err("Cannot read back a Window target".into()),
+            None => Err(format!("Render Target {:?} not found!", id).into()),
+        }
+    }
+
+    /// Copies a region of a Texture target to a staging buffer.
+    ///
+    /// Returns None if the region is empty.
+    pub(crate) fn target_region_readback(
+        &self,
+        id: &TargetId,
+        region: Quad,
+    ) -> Result<Option<Readback>, Error> {
+        let targets = self.read_targets()?;
+        match targets.get(id) {
+            Some(RenderTarget::Texture(target)) => Ok(target.region_readback(self, region)),
+            Some(RenderTarget::Window(_)) => Err("Cannot read back a Window target".into()),
+            None => Err(format!("Render Target {:?} not found!", id).into()),
+        }
+    }
+
+    /// Copies a region of one texture into another on the GPU.
+    ///
+    /// Both textures can be loaded textures or Texture targets. They
+    /// must have the same format, the source needs COPY_SRC usage and
+    /// the destination needs COPY_DST. The region defaults to the whole
+    /// source texture and is copied to the same position in the destination.
+    ///
+    /// A texture can't be copied into itself, as the source and destination
+    /// regions would always overlap.
+    pub(crate) fn copy_texture(
+        &self,
+        source: &TextureId,
+        destination: &TextureId,
+        region: Option<Quad>,
+    ) -> Result<(), Error> {
+        if source == destination {
+            return Err(format!("Cannot copy texture {:?} into itself", source).into());
+        }
+
+        let resources = self.read_resources()?;
+        let targets = self.read_targets()?;
+
+        let source = Internal::find_texture(&resources, &targets, source)
+            .ok_or_else(|| format!("Source texture {:?} not found!", source))?;
+        let destination = Internal::find_texture(&resources, &targets, destination)
+            .ok_or_else(|| format!("Destination texture {:?} not found!", destination))?;
+
+        if source.format != destination.format {
+            return Err(format!(
+                "Cannot copy a {:?} texture into a {:?} texture",
+                source.format, destination.format
+            )
+            .into());
+        }
+        if !source.has_usage(wgpu::TextureUsages::COPY_SRC) {
+            return Err("The source texture does not allow COPY_SRC usage".into());
+        }
+        if !destination.has_usage(wgpu::TextureUsages::COPY_DST) {
+            return Err("The destination texture does not allow COPY_DST usage".into());
+        }
+
+        let region = region.unwrap_or(source.size());
+        let fits = |size: Quad| size.intersect(region) == Some(region);
+        if !fits(source.size()) || !fits(destination.size()) {
+            return Err(format!("Region {:?} is outside of the textures", region).into());
+        }
+
+        let origin = wgpu::Origin3d {
+            x: region.min_x,
+            y: region.min_y,
+            z: 0,
+        };
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Copy Texture"),
+            });
+        encoder.copy_texture_to_texture(
+            wgpu::ImageCopyTexture {
+                origin,
+                ..source.data.as_image_copy()
+            },
+            wgpu::ImageCopyTexture {
+                origin,
+                ..destination.data.as_image_copy()
+            },
+            region.to_wgpu_size(),
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// The shader and pipelines shared by every Blit
+    pub(crate) fn blit_pipelines(&self) -> &BlitPipelines {
+        &self.blit_pipelines
+    }
+
+    /// Draws a texture over a whole render target, converting its format.
+    pub(crate) fn blit(&self, source: &TextureId, target: &TargetId) -> Result<(), Error> {
+        self.tonemap(source, target, ToneMapOptions::default())
+    }
+
+    /// Draws a texture over a whole render target, tone mapping its colors.
+    pub(crate) fn tonemap(
+        &self,
+        source: &TextureId,
+        target: &TargetId,
+        options: ToneMapOptions,
+    ) -> Result<(), Error> {
+        let (commands, frames) = {
+            let resources = self.read_resources()?;
+            let targets = self.read_targets()?;
+
+            let source = Internal::find_texture(&resources, &targets, source)
+                .ok_or_else(|| format!("Source texture {:?} not found!", source))?;
+            if !source.has_usage(wgpu::TextureUsages::TEXTURE_BINDING) {
+                return Err("The source texture does not allow TEXTURE_BINDING usage".into());
+            }
+
+            let target = targets
+                .get(target)
+                .ok_or_else(|| format!("Render target {:?} not found!", target))?;
+            if target.id() == TargetId::Texture(source.id) {
+                return Err("Cannot blit a texture into itself".into());
+            }
+
+            Blit::new(self, target).draw(source, target, options)?
+        };
+
+        Ok(self.submit(commands, frames)?)
+    }
+
     /// Removes a rendering target from the renderer.
     pub(crate) fn remove_target(&self, id: &TargetId) -> Result<Option<RenderTarget>, Error> {
+        if let Ok(mut depth_buffers) = self.depth_buffers.write() {
+            depth_buffers.remove(id);
+        }
         if let Ok(mut targets) = self.write_targets() {
             Ok(targets.remove(id))
         } else {
@@ -245,7 +639,68 @@ impl Renderer {
     /// Where the magic starts! 🪄
     ///
     /// Selects a RenderPass to render a frame from the given Scene
-    pub(crate) fn render(&self, scene: &Scene) -> Result<(), wgpu::SurfaceError> {
+    pub(crate) fn render(&self, scene: &Scene) -> Result<(), RendererError> {
+        self.render_batch(&[scene])
+    }
+
+    /// Renders many Scenes with a single queue submission.
+    ///
+    /// On native, GPU errors raised while rendering are captured by error
+    /// scopes and returned, or panic if `panic_on_error` is set. On the
+    /// Web, popping a scope would need to wait for the browser, so they
+    /// go to the uncaptured error handler instead.
+    pub(crate) fn render_batch(&self, scenes: &[&Scene]) -> Result<(), RendererError> {
+        #[cfg(not(wasm))]
+        {
+            self.device.push_error_scope(wgpu::ErrorFilter::OutOfMemory);
+            self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        }
+
+        let result = self.record_and_submit(scenes);
+
+        #[cfg(not(wasm))]
+        {
+            // Native error scopes resolve right away, so this doesn't wait for the GPU
+            let validation = pollster::block_on(self.device.pop_error_scope());
+            let out_of_memory = pollster::block_on(self.device.pop_error_scope());
+            if let Some(error) = validation.or(out_of_memory) {
+                log::error!("\n\n==== GPU error: ====\n\n{:#?}\n", error);
+                if self.panic_on_error {
+                    panic!("GPU error: {}", error);
+                }
+                return Err(RendererError::Gpu(error));
+            }
+        }
+
+        result
+    }
+
+    // A target frame can only be acquired once before it's presented,
+    // so the batch is submitted early if a Scene renders to a target
+    // that was already used in the batch.
+    fn record_and_submit(&self, scenes: &[&Scene]) -> Result<(), RendererError> {
+        let mut commands = Commands::new();
+        let mut frames = RenderedFrames::new();
+        let mut used_targets = HashSet::new();
+
+        for scene in scenes {
+            let target_ids = scene.read_state().target_ids();
+            if target_ids.iter().any(|id| used_targets.contains(id)) {
+                self.submit(std::mem::take(&mut commands), std::mem::take(&mut frames))?;
+                used_targets.clear();
+            }
+            used_targets.extend(target_ids);
+
+            let (mut scene_commands, mut scene_frames) = self.record(scene)?;
+            commands.append(&mut scene_commands);
+            frames.append(&mut scene_frames);
+        }
+
+        self.submit(commands, frames)
+    }
+
+    // Selects the configured RenderPass and records its commands
+    fn record(&self, scene: &Scene) -> RenderPassResult {
         if self.pass == "solid" {
             return self.solid_renderpass(scene);
         }
@@ -253,10 +708,11 @@ impl Renderer {
     }
 
     // Renders the Solid 3D render pass (for simple 3D primitives)
-    fn solid_renderpass(&self, scene: &Scene) -> Result<(), wgpu::SurfaceError> {
+    fn solid_renderpass(&self, scene: &Scene) -> RenderPassResult {
         let renderpass = crate::renderer::renderpass::Solid::new(
             &crate::renderer::renderpass::SolidConfig {
-                cull_back_faces: true,
+                primitive: self.primitive,
+                depth: self.depth,
             },
             self,
         );
@@ -265,7 +721,7 @@ impl Renderer {
     }
 
     // Renders the Shadertoy render pass (for a single fullscreen quad)
-    fn toy_renderpass(&self, scene: &Scene) -> Result<(), wgpu::SurfaceError> {
+    fn toy_renderpass(&self, scene: &Scene) -> RenderPassResult {
         let renderpass = crate::renderer::renderpass::Toy::new(self);
 
         self.draw(scene, renderpass)
@@ -273,24 +729,38 @@ impl Renderer {
 
     // Where the magic happens! 🎨
     //
-    // Renders a frame from the given Scene with the given RenderPass
-    fn draw<P: RenderPass>(
-        &self,
-        scene: &Scene,
-        mut renderpass: P,
-    ) -> Result<(), wgpu::SurfaceError> {
-        // Records the render commands in the GPU command buffer
-        let (commands, frames) = renderpass.draw(scene.read_state())?;
+    // Records the render commands of a frame from the given Scene with the given RenderPass
+    fn draw<P: RenderPass>(&self, scene: &Scene, mut renderpass: P) -> RenderPassResult {
+        renderpass.draw(scene.read_state())
+    }
+
+    // Runs the recorded commands and shows the rendered frames
+    fn submit(&self, mut commands: Commands, frames: RenderedFrames) -> Result<(), RendererError> {
+        // Copies the timestamps of the timed passes to a readable buffer
+        let timings = match self.timer.read().as_deref() {
+            Ok(Some(timer)) => timer.resolve(&self.device),
+            _ => None,
+        };
+        let timings = timings.map(|(resolve_commands, timings)| {
+            commands.push(resolve_commands);
+            timings
+        });
 
         // Runs the commands (submit to GPU queue)
         self.queue.submit(commands);
 
+        if let Some(timings) = timings {
+            timings.read();
+            // Runs the mapping callbacks of previous frames that are done
+            self.device.poll(wgpu::Maintain::Poll);
+        }
+
         // Shows the rendered frames on the screen
         if let Ok(mut targets) = self.write_targets() {
             targets.present(frames);
         } else {
             log::warn!("Dropped Frame: Cannot present! Failed to acquire Render Targets Database Write lock.");
-            return Err(wgpu::SurfaceError::Lost);
+            return Err(RendererError::Locked);
         };
 
         Ok(())
@@ -300,6 +770,20 @@ impl Renderer {
 // Helper static methods
 struct Internal;
 impl Internal {
+    /// Finds a loaded texture or the texture of a Texture target
+    fn find_texture<'a>(
+        resources: &'a Resources,
+        targets: &'a RenderTargets,
+        id: &TextureId,
+    ) -> Option<&'a Texture> {
+        resources
+            .get_texture(id)
+            .or_else(|| match targets.get(&TargetId::Texture(*id)) {
+                Some(RenderTarget::Texture(target)) => Some(&target.texture),
+                _ => None,
+            })
+    }
+
     async fn gpu_objects<W: IsWindow>(
         options: RendererOptions,
         window: Option<&W>,
@@ -314,7 +798,10 @@ impl Internal {
         Error,
     > {
         let panic_on_device_error = options.panic_on_error;
-        let instance = wgpu::Instance::default();
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: Internal::backends(&options.backends),
+            ..Default::default()
+        });
         let (power_preference, force_fallback_adapter, limits) = Internal::parse_options(options);
         let surface = if let Ok(surface) = Internal::surface(&instance, window) {
             Some(surface)
@@ -331,10 +818,13 @@ impl Internal {
             .await
             .ok_or("Failed to find an appropriate GPU adapter")?;
 
+        let features = adapter.features()
+            & (COMPRESSED_TEXTURE_FEATURES | TIMING_FEATURES | PUSH_CONSTANT_FEATURES);
+        let limits = Internal::push_constant_limits(limits, features, &adapter.limits());
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
+                    features,
                     limits,
                     label: None,
                 },
@@ -369,6 +859,27 @@ impl Internal {
         (power_preference, force_fallback_adapter, device_limits)
     }
 
+    fn backends(backends: &str) -> wgpu::Backends {
+        let selected = backends
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .fold(wgpu::Backends::empty(), |selected, name| {
+                if let Some(backend) = BACKENDS.get(name) {
+                    selected | *backend
+                } else {
+                    log::warn!("Unknown backend '{}'. Ignoring it.", name);
+                    selected
+                }
+            });
+
+        if selected.is_empty() {
+            wgpu::Backends::all()
+        } else {
+            selected
+        }
+    }
+
     fn surface<W: IsWindow>(
         instance: &wgpu::Instance,
         window: Option<&W>,
@@ -414,13 +925,17 @@ impl Internal {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_capabilities.formats[0]);
 
-        // alpha_mode should be transparent if the surface supports it
-        let alpha_mode = surface_capabilities
-            .alpha_modes
-            .iter()
-            .find(|m| *m == &wgpu::CompositeAlphaMode::PreMultiplied)
-            .unwrap_or(&wgpu::CompositeAlphaMode::Auto)
-            .to_owned();
+        let (present_mode, alpha_mode) = if let Ok(state) = window.state().read() {
+            (
+                Self::present_mode(&state.present_mode, &surface_capabilities.present_modes),
+                Self::alpha_mode(&state.alpha_mode, &surface_capabilities.alpha_modes),
+            )
+        } else {
+            (
+                surface_capabilities.present_modes[0],
+                Self::alpha_mode("default", &surface_capabilities.alpha_modes),
+            )
+        };
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -428,7 +943,7 @@ impl Internal {
             width: window.size().width(),
             height: window.size().height(),
             alpha_mode,
-            present_mode: surface_capabilities.present_modes[0],
+            present_mode,
             view_formats: vec![],
         };
 
@@ -442,10 +957,114 @@ impl Internal {
         })
     }
 
-    /// Creates the shared default blank pixel texture.
-    fn create_default_blank_pixel(
+    /// Picks the requested present mode if the surface supports it.
+    ///
+    /// Falls back to Fifo (vsync), which every surface supports.
+    fn present_mode(requested: &str, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+        if requested.is_empty() || requested == "default" {
+            return supported[0];
+        }
+
+        let present_mode = if let Some(present_mode) = PRESENT_MODES.get(requested) {
+            *present_mode
+        } else {
+            log::warn!("Unknown present mode '{}'. Using vsync instead.", requested);
+            return wgpu::PresentMode::Fifo;
+        };
+
+        let is_automatic = matches!(
+            present_mode,
+            wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+        );
+        if is_automatic || supported.contains(&present_mode) {
+            present_mode
+        } else {
+            log::warn!(
+                "Present mode '{}' is not supported by this surface. Using vsync instead.",
+                requested
+            );
+            wgpu::PresentMode::Fifo
+        }
+    }
+
+    /// Picks the requested alpha mode if the surface supports it.
+    ///
+    /// By default, alpha_mode should be transparent if the surface supports it.
+    fn alpha_mode(
+        requested: &str,
+        supported: &[wgpu::CompositeAlphaMode],
+    ) -> wgpu::CompositeAlphaMode {
+        let transparent = wgpu::CompositeAlphaMode::PreMultiplied;
+        let default = if supported.contains(&transparent) {
+            transparent
+        } else {
+            wgpu::CompositeAlphaMode::Auto
+        };
+
+        if requested.is_empty() || requested == "default" {
+            return default;
+        }
+
+        let alpha_mode = if let Some(alpha_mode) = ALPHA_MODES.get(requested) {
+            *alpha_mode
+        } else {
+            log::warn!("Unknown alpha mode '{}'. Using the default.", requested);
+            return default;
+        };
+
+        if alpha_mode == wgpu::CompositeAlphaMode::Auto || supported.contains(&alpha_mode) {
+            alpha_mode
+        } else {
+            log::warn!(
+                "Alpha mode '{}' is not supported by this surface. Supported modes: {:?}",
+                requested,
+                supported
+            );
+            default
+        }
+    }
+
+    /// Raises the push constant size to the adapter's maximum
+    /// if the push constants feature is requested.
+    ///
+    /// The limit presets allow no push constants, as most Web
+    /// and downlevel backends don't support them.
+    fn push_constant_limits(
+        limits: wgpu::Limits,
+        features: wgpu::Features,
+        adapter_limits: &wgpu::Limits,
+    ) -> wgpu::Limits {
+        if features.contains(wgpu::Features::PUSH_CONSTANTS) {
+            wgpu::Limits {
+                max_push_constant_size: adapter_limits.max_push_constant_size,
+                ..limits
+            }
+        } else {
+            limits
+        }
+    }
+
+    /// Creates the depth buffer of a Render Target.
+    fn create_depth_view(device: &wgpu::Device, size: wgpu::Extent3d) -> wgpu::TextureView {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("depth"),
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            size,
+            sample_count: 1,
+            mip_level_count: 1,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[DEPTH_FORMAT],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Creates a shared 1x1 texture with a single color.
+    fn create_pixel_texture(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
+        label: &str,
+        color: Color,
     ) -> Result<Texture, Error> {
         let size = wgpu::Extent3d {
             width: 1,
@@ -455,7 +1074,7 @@ impl Internal {
         let format = wgpu::TextureFormat::Rgba8UnormSrgb;
 
         let descriptor = wgpu::TextureDescriptor {
-            label: Some("Default Blank Pixel"),
+            label: Some(label),
             size,
             mip_level_count: 1,
             sample_count: 1,
@@ -465,15 +1084,15 @@ impl Internal {
             view_formats: &[],
         };
 
-        let texture = device.create_texture_with_data(queue, &descriptor, &[0xFF; 4]);
+        let texture = device.create_texture_with_data(queue, &descriptor, &color.0.to_be_bytes());
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         let sampler = create_sampler(
             device,
             SamplerOptions {
-                repeat_x: true,
-                repeat_y: true,
-                smooth: false,
-                compare: None,
+                mag_filter: wgpu::FilterMode::Nearest,
+                min_filter: wgpu::FilterMode::Nearest,
+                mipmap_filter: wgpu::FilterMode::Nearest,
+                ..SamplerOptions::repeat()
             },
         );
 
@@ -487,3 +1106,120 @@ impl Internal {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::{CompositeAlphaMode, PresentMode};
+
+    #[test]
+    fn present_mode_defaults_to_the_first_supported_mode() {
+        let supported = [PresentMode::Mailbox, PresentMode::Fifo];
+
+        assert_eq!(Internal::present_mode("", &supported), PresentMode::Mailbox);
+        assert_eq!(
+            Internal::present_mode("default", &supported),
+            PresentMode::Mailbox
+        );
+    }
+
+    #[test]
+    fn present_mode_falls_back_to_vsync() {
+        let supported = [PresentMode::Mailbox, PresentMode::Fifo];
+
+        assert_eq!(
+            Internal::present_mode("immediate", &supported),
+            PresentMode::Fifo
+        );
+        assert_eq!(
+            Internal::present_mode("tearing", &supported),
+            PresentMode::Fifo
+        );
+        assert_eq!(
+            Internal::present_mode("mailbox", &supported),
+            PresentMode::Mailbox
+        );
+    }
+
+    #[test]
+    fn push_constants_use_the_adapter_limit_when_supported() {
+        let adapter_limits = wgpu::Limits {
+            max_push_constant_size: 128,
+            ..Default::default()
+        };
+
+        let limits = Internal::push_constant_limits(
+            wgpu::Limits::default(),
+            wgpu::Features::PUSH_CONSTANTS,
+            &adapter_limits,
+        );
+        assert_eq!(limits.max_push_constant_size, 128);
+
+        let limits = Internal::push_constant_limits(
+            wgpu::Limits::default(),
+            wgpu::Features::empty(),
+            &adapter_limits,
+        );
+        assert_eq!(limits.max_push_constant_size, 0);
+    }
+
+    #[test]
+    fn present_mode_accepts_automatic_modes() {
+        let supported = [PresentMode::Fifo];
+
+        assert_eq!(
+            Internal::present_mode("auto_vsync", &supported),
+            PresentMode::AutoVsync
+        );
+        assert_eq!(
+            Internal::present_mode("auto_no_vsync", &supported),
+            PresentMode::AutoNoVsync
+        );
+    }
+
+    #[test]
+    fn alpha_mode_defaults_to_transparent_when_supported() {
+        let transparent = [
+            CompositeAlphaMode::Opaque,
+            CompositeAlphaMode::PreMultiplied,
+        ];
+        let opaque = [CompositeAlphaMode::Opaque];
+
+        assert_eq!(
+            Internal::alpha_mode("default", &transparent),
+            CompositeAlphaMode::PreMultiplied
+        );
+        assert_eq!(Internal::alpha_mode("", &opaque), CompositeAlphaMode::Auto);
+    }
+
+    #[test]
+    fn alpha_mode_falls_back_to_the_default() {
+        let supported = [
+            CompositeAlphaMode::Opaque,
+            CompositeAlphaMode::PreMultiplied,
+        ];
+
+        assert_eq!(
+            Internal::alpha_mode("postmultiplied", &supported),
+            CompositeAlphaMode::PreMultiplied
+        );
+        assert_eq!(
+            Internal::alpha_mode("see-through", &supported),
+            CompositeAlphaMode::PreMultiplied
+        );
+        assert_eq!(
+            Internal::alpha_mode("opaque", &supported),
+            CompositeAlphaMode::Opaque
+        );
+    }
+
+    #[test]
+    fn alpha_mode_accepts_auto() {
+        let supported = [CompositeAlphaMode::Opaque];
+
+        assert_eq!(
+            Internal::alpha_mode("auto", &supported),
+            CompositeAlphaMode::Auto
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{
+    renderer::{
+        renderpass::{pop_debug_group, push_debug_group},
+        IsRenderTarget, RenderContext, RenderPassResult, RenderTarget, Renderer, ToneMapOptions,
+        ToneMapping,
+    },
+    Texture,
+};
+use fxhash::FxHashMap;
+use std::{
+    mem,
+    sync::{Arc, RwLock},
+};
+
+/// @Group(0) @Binding(2)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ToneMapUniforms {
+    exposure: f32,
+    operator: u32,
+    padding: [f32; 2], // unused; needed for alignment
+}
+
+impl From<ToneMapOptions> for ToneMapUniforms {
+    fn from(options: ToneMapOptions) -> Self {
+        let operator = match options.operator {
+            ToneMapping::None => 0,
+            ToneMapping::Reinhard => 1,
+            ToneMapping::Aces => 2,
+        };
+
+        Self {
+            exposure: options.exposure,
+            operator,
+            padding: [0.0; 2],
+        }
+    }
+}
+
+/// The shader, layouts and pipelines shared by every Blit.
+///
+/// Owned by the Renderer, so blitting many times doesn't recompile
+/// the shader. One pipeline is built for each target format and
+/// sample count, the first time a target like that is blitted into.
+#[derive(Debug)]
+pub(crate) struct BlitPipelines {
+    shader_module: wgpu::ShaderModule,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+    pipelines: RwLock<FxHashMap<(wgpu::TextureFormat, u32), Arc<wgpu::RenderPipeline>>>,
+}
+
+impl BlitPipelines {
+    pub(crate) fn new(device: &wgpu::Device) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Renderpass: Shader Module"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit.wgsl").into()),
+        });
+
+        let tonemap_buffer_size = mem::size_of::<ToneMapUniforms>() as wgpu::BufferAddress;
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Blit Renderpass: Source Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: wgpu::BufferSize::new(tonemap_buffer_size),
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blit Renderpass: Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Blit Renderpass: Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            shader_module,
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+            pipelines: RwLock::new(FxHashMap::default()),
+        }
+    }
+
+    /// How many pipelines were built so far, one per target format and sample count
+    pub(crate) fn pipeline_count(&self) -> usize {
+        self.pipelines
+            .read()
+            .map(|pipelines| pipelines.len())
+            .unwrap_or(0)
+    }
+
+    /// Returns the pipeline for the target, building it the first time.
+    fn pipeline(&self, device: &wgpu::Device, target: &RenderTarget) -> Arc<wgpu::RenderPipeline> {
+        let key = (target.format(), target.sample_count());
+        if let Some(pipeline) = self
+            .pipelines
+            .read()
+            .ok()
+            .and_then(|pipelines| pipelines.get(&key).cloned())
+        {
+            return pipeline;
+        }
+
+        let (format, sample_count) = key;
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Blit Render Pipeline"),
+            layout: Some(&self.pipeline_layout),
+            vertex: wgpu::VertexState {
+                buffers: &[],
+                module: &self.shader_module,
+                entry_point: "main_vs",
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            fragment: Some(wgpu::FragmentState {
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::all(),
+                })],
+                module: &self.shader_module,
+                entry_point: "main_fs",
+            }),
+            multiview: None,
+        });
+        let pipeline = Arc::new(pipeline);
+
+        if let Ok(mut pipelines) = self.pipelines.write() {
+            pipelines.insert(key, pipeline.clone());
+        }
+
+        pipeline
+    }
+}
+
+/// Draws a texture stretched over a whole render target.
+///
+/// The source must be a filterable float texture, like the ones
+/// loaded from images or HDR (Rgba16Float) targets. The target
+/// can use any color format. Colors are tone mapped on the way.
+pub(crate) struct Blit<'r> {
+    renderer: &'r Renderer,
+    pipelines: &'r BlitPipelines,
+    tonemap_uniform_buffer: wgpu::Buffer,
+    pipeline: Arc<wgpu::RenderPipeline>,
+}
+
+impl<'r> Blit<'r> {
+    pub(crate) fn new(renderer: &'r Renderer, target: &RenderTarget) -> Self {
+        let device = renderer.device();
+        let pipelines = renderer.blit_pipelines();
+
+        let tonemap_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Blit Renderpass: Tone Map Uniform Buffer"),
+            size: mem::size_of::<ToneMapUniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            renderer,
+            pipelines,
+            tonemap_uniform_buffer,
+            pipeline: pipelines.pipeline(device, target),
+        }
+    }
+
+    /// Records the commands to draw the source texture into the target
+    pub(crate) fn draw(
+        &self,
+        source: &Texture,
+        target: &RenderTarget,
+        options: ToneMapOptions,
+    ) -> RenderPassResult {
+        let renderer = self.renderer;
+        let device = renderer.device();
+
+        renderer.queue().write_buffer(
+            &self.tonemap_uniform_buffer,
+            0,
+            bytemuck::bytes_of(&ToneMapUniforms::from(options)),
+        );
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Blit Renderpass: Source Bind Group"),
+            layout: &self.pipelines.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&source.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.pipelines.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.tonemap_uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let frame = target.next_frame()?;
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Blit Texture"),
+        });
+        push_debug_group(
+            renderer,
+            &mut encoder,
+            format_args!("blit: {:?}", target.id()),
+        );
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Blit Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &frame.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                ..Default::default()
+            });
+
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+        pop_debug_group(renderer, &mut encoder);
+
+        let mut commands = vec![encoder.finish()];
+        target.prepare_render(renderer, &mut commands);
+
+        Ok((commands, vec![(target.id(), frame)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{renderer::target::RenderTargetDescription, FragmentColor, Quad, Texture};
+
+    #[test]
+    fn blits_reuse_the_pipeline_of_their_target_format() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]))
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let (source, _) = Texture::from_bytes(&png.into_inner()).unwrap();
+
+        let size = Quad::from_size(4, 4);
+        let first = RenderTargetDescription::create_texture_target(size).unwrap();
+        let second = RenderTargetDescription::create_texture_target(size).unwrap();
+
+        Texture::blit(&source, &first.target_id).unwrap();
+        let pipelines = FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .blit_pipelines()
+            .pipeline_count();
+        Texture::blit(&source, &second.target_id).unwrap();
+        let bytes = second.target_id.read_region_blocking(size).unwrap();
+
+        let renderer = FragmentColor::renderer();
+        let renderer = renderer.try_read().unwrap();
+        assert_eq!(renderer.blit_pipelines().pipeline_count(), pipelines);
+        renderer.remove_target(&first.target_id).unwrap();
+        renderer.remove_target(&second.target_id).unwrap();
+        drop(renderer);
+        Texture::free(&source).unwrap();
+
+        for pixel in bytes.chunks_exact(4) {
+            assert_eq!(pixel, [255, 0, 0, 255]);
+        }
+    }
+}
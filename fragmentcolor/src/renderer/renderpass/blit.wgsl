@@ -0,0 +1,59 @@
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// A single triangle that covers the whole screen:
+// (-1, 1), (3, 1) and (-1, -3) in clip space.
+@vertex
+fn main_vs(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+
+struct ToneMap {
+    exposure: f32,
+    operator: u32, // 0: None, 1: Reinhard, 2: ACES
+};
+@group(0) @binding(2)
+var<uniform> tonemap: ToneMap;
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (1.0 + color);
+}
+
+// Narkowicz 2015, "ACES Filmic Tone Mapping Curve"
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    let mapped = (color * (a * color + b)) / (color * (c * color + d) + e);
+    return clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// The hardware converts between sRGB and linear
+// formats when sampling and writing the colors.
+@fragment
+fn main_fs(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = textureSample(source, source_sampler, in.uv);
+    var color = texel.rgb * tonemap.exposure;
+
+    if tonemap.operator == 1u {
+        color = reinhard(color);
+    } else if tonemap.operator == 2u {
+        color = aces(color);
+    }
+
+    return vec4<f32>(color, texel.a);
+}
@@ -1,22 +1,68 @@
+mod blit;
 mod buffer;
 mod phong;
 mod real;
 mod solid;
 mod toy;
 
+pub(crate) use blit::*;
 pub(crate) use phong::*;
 pub(crate) use real::*;
 pub(crate) use solid::*;
 pub(crate) use toy::*;
 
 use crate::{
-    renderer::{Commands, RenderedFrames},
+    renderer::{Commands, Frame, IsRenderTarget, RenderTarget, RenderedFrames, Renderer},
     scene::SceneState,
 };
 use std::sync::RwLockReadGuard;
 
+/// Format of the depth buffers of the 3D RenderPasses.
+pub(crate) const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24Plus;
+
 pub(crate) type RenderPassResult = Result<(Commands, RenderedFrames), wgpu::SurfaceError>;
 
 pub(crate) trait RenderPass {
     fn draw(&mut self, scene: RwLockReadGuard<'_, SceneState>) -> RenderPassResult;
 }
+
+/// Acquires the next frame of one of many targets of a pass.
+///
+/// Each Window surface is acquired and presented independently, so
+/// a Lost, Outdated or Timeout surface only skips its own target.
+/// Windows reconfigure their surface when they are resized.
+///
+/// Running out of memory affects every target, so it stops the pass.
+pub(super) fn next_frame(target: &RenderTarget) -> Result<Option<Frame>, wgpu::SurfaceError> {
+    match target.next_frame() {
+        Ok(frame) => Ok(Some(frame)),
+        Err(wgpu::SurfaceError::OutOfMemory) => Err(wgpu::SurfaceError::OutOfMemory),
+        Err(error) => {
+            log::warn!(
+                "Dropped Frame: Cannot acquire the next frame of {:?}: {}",
+                target.id(),
+                error
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Opens a debug group, shown as a level of hierarchy in GPU debuggers
+/// like RenderDoc and Xcode. Does nothing unless debug labels are enabled.
+pub(super) fn push_debug_group(
+    renderer: &Renderer,
+    encoder: &mut wgpu::CommandEncoder,
+    label: std::fmt::Arguments,
+) {
+    if renderer.debug_labels() {
+        encoder.push_debug_group(&label.to_string());
+    }
+}
+
+/// Closes the debug group opened by `push_debug_group`.
+pub(super) fn pop_debug_group(renderer: &Renderer, encoder: &mut wgpu::CommandEncoder) {
+    if renderer.debug_labels() {
+        encoder.pop_debug_group();
+    }
+}
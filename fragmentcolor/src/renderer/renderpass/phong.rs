@@ -3,8 +3,10 @@ use crate::{
     math::geometry::vertex,
     math::geometry::vertex::{Normal, Position, Vertex},
     renderer::{
-        renderpass::buffer, target::Dimensions, IsRenderTarget, RenderContext, RenderPass,
-        RenderPassResult, RenderTargetCollection, Renderer,
+        renderpass::{buffer, next_frame, pop_debug_group, push_debug_group, DEPTH_FORMAT},
+        target::Dimensions,
+        DepthOptions, IsRenderTarget, PrimitiveOptions, RenderContext, RenderPass,
+        RenderPassResult, RenderTargetCollection, Renderer, TimedPass,
     },
     scene::SceneState,
     Color,
@@ -18,7 +20,6 @@ pub(crate) enum ShaderType {
     Phong { glossiness: u8 },
 }
 
-const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24Plus;
 const INTENSITY_THRESHOLD: f32 = 0.1;
 const LIGHT_COUNT: usize = 4;
 
@@ -77,7 +78,8 @@ impl Default for Ambient {
 
 #[derive(Debug)]
 pub struct PhongConfig {
-    pub cull_back_faces: bool,
+    pub primitive: PrimitiveOptions,
+    pub depth: DepthOptions,
     pub ambient: Ambient,
     pub max_lights: usize,
 }
@@ -85,7 +87,8 @@ pub struct PhongConfig {
 impl Default for PhongConfig {
     fn default() -> Self {
         Self {
-            cull_back_faces: true,
+            primitive: PrimitiveOptions::default(),
+            depth: DepthOptions::default(),
             ambient: Ambient::default(),
             max_lights: 16,
         }
@@ -94,7 +97,7 @@ impl Default for PhongConfig {
 
 pub(crate) struct Phong<'r> {
     renderer: &'r Renderer,
-    depth_texture: Option<(wgpu::TextureView, wgpu::Extent3d)>,
+    depth: DepthOptions,
     global_uniform_buf: wgpu::Buffer,
     light_buf: wgpu::Buffer,
     light_capacity: usize,
@@ -197,21 +200,8 @@ impl<'r> Phong<'r> {
                 vertex::Position::layout::<0>(),
                 vertex::Normal::layout::<1>(),
             ];
-            let primitive = wgpu::PrimitiveState {
-                cull_mode: if config.cull_back_faces {
-                    Some(wgpu::Face::Back)
-                } else {
-                    None
-                },
-                ..Default::default()
-            };
-            let ds = Some(wgpu::DepthStencilState {
-                format: DEPTH_FORMAT,
-                depth_compare: wgpu::CompareFunction::LessEqual,
-                depth_write_enabled: true,
-                bias: Default::default(),
-                stencil: Default::default(),
-            });
+            let primitive = wgpu::PrimitiveState::from(config.primitive);
+            let ds = Some(config.depth.state(DEPTH_FORMAT));
 
             let mut sample_count = 1;
             let targets = &renderer
@@ -238,7 +228,9 @@ impl<'r> Phong<'r> {
 
                     Some(wgpu::ColorTargetState {
                         format: target.format(),
-                        blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        blend: Some(
+                            renderer.blend_state(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        ),
                         write_mask: wgpu::ColorWrites::all(),
                     })
                 })
@@ -309,7 +301,7 @@ impl<'r> Phong<'r> {
 
         Self {
             renderer,
-            depth_texture: None,
+            depth: config.depth,
             global_uniform_buf,
             light_capacity: config.max_lights,
             light_buf,
@@ -352,24 +344,8 @@ impl<'r> RenderPass for Phong<'r> {
 
                 let target = target.unwrap();
 
-                let reset_depth = match self.depth_texture {
-                    Some((_, size)) => size != target.size().to_wgpu_size(),
-                    None => true,
-                };
-                if reset_depth {
-                    let texture = device.create_texture(&wgpu::TextureDescriptor {
-                        label: Some("depth"),
-                        dimension: wgpu::TextureDimension::D2,
-                        format: DEPTH_FORMAT,
-                        size: target.size().to_wgpu_size(),
-                        sample_count: 1,
-                        mip_level_count: 1,
-                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                        view_formats: &[DEPTH_FORMAT],
-                    });
-                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-                    self.depth_texture = Some((view, target.size().to_wgpu_size()));
-                }
+                let (depth_view, new_depth_buffer) =
+                    renderer.depth_buffer(target.id(), target.size().to_wgpu_size());
 
                 let transforms = scene.calculate_global_transforms();
                 self.uniform_pool.reset();
@@ -448,33 +424,54 @@ impl<'r> RenderPass for Phong<'r> {
 
                 let mut encoder =
                     device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+                let timed_pass = renderer.time_pass(format_args!("phong: {:?}", target.id()));
+                push_debug_group(
+                    renderer,
+                    &mut encoder,
+                    format_args!("phong: {:?}", target.id()),
+                );
 
-                let frame = target.next_frame()?;
+                let frame = if let Some(frame) = next_frame(target)? {
+                    frame
+                } else {
+                    continue;
+                };
 
                 let resources = renderer.read_resources().expect("lock poisoned");
 
-                {
+                'draw: {
                     let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: Some("phong"),
                         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                             view: &frame.view,
                             resolve_target: None,
                             ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(camera_target.clear_color.into()),
+                                load: wgpu::LoadOp::Clear(
+                                    camera_target.clear_color.to_wgpu_color(target.format()),
+                                ),
                                 store: wgpu::StoreOp::Store,
                             },
                         })],
                         depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.depth_texture.as_ref().unwrap().0,
+                            view: &depth_view,
                             depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(1.0),
+                                load: self
+                                    .depth
+                                    .load_op(new_depth_buffer, camera_target.load_depth),
                                 store: wgpu::StoreOp::Store,
                             }),
                             stencil_ops: None,
                         }),
+                        timestamp_writes: timed_pass.as_ref().map(TimedPass::writes),
                         ..Default::default()
                     });
 
+                    if !camera_target.apply_viewport(&mut pass, target.size())
+                        || !camera_target.apply_scissor(&mut pass, target.size())
+                    {
+                        break 'draw;
+                    }
+
                     pass.set_bind_group(0, &self.global_bind_group, &[]);
 
                     for (_, (entity, &color, &shader)) in scene
@@ -563,6 +560,7 @@ impl<'r> RenderPass for Phong<'r> {
                     }
                 }
 
+                pop_debug_group(renderer, &mut encoder);
                 commands.append(&mut vec![encoder.finish()]);
                 target.prepare_render(renderer, &mut commands);
 
@@ -1,8 +1,10 @@
 use crate::{
     math::geometry::{Normal, Position, TextureCoordinates, Vertex},
     renderer::{
-        renderpass::buffer, target::Dimensions, IsRenderTarget, RenderContext, RenderPassResult,
-        RenderTargetCollection, Renderer,
+        renderpass::{buffer, next_frame, pop_debug_group, push_debug_group, DEPTH_FORMAT},
+        target::Dimensions,
+        DepthOptions, IsRenderTarget, PrimitiveOptions, RenderContext, RenderPassResult,
+        RenderTargetCollection, Renderer, TimedPass,
     },
     resources::{mesh::MeshId, texture::TextureId},
     scene::SceneState,
@@ -34,8 +36,6 @@ impl Default for Material {
     }
 }
 
-const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24Plus;
-
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct Globals {
@@ -72,14 +72,16 @@ struct LocalKey {
 
 #[derive(Debug)]
 pub struct RealConfig {
-    pub cull_back_faces: bool,
+    pub primitive: PrimitiveOptions,
+    pub depth: DepthOptions,
     pub max_lights: usize,
 }
 
 impl Default for RealConfig {
     fn default() -> Self {
         Self {
-            cull_back_faces: true,
+            primitive: PrimitiveOptions::default(),
+            depth: DepthOptions::default(),
             max_lights: 16,
         }
     }
@@ -99,7 +101,7 @@ struct Instance {
 /// Follows Disney PBR.
 pub(crate) struct Real<'r> {
     renderer: &'r Renderer,
-    depth_texture: Option<(wgpu::TextureView, wgpu::Extent3d)>,
+    depth: DepthOptions,
     global_uniform_buf: wgpu::Buffer,
     light_buf: wgpu::Buffer,
     light_capacity: usize,
@@ -225,14 +227,7 @@ impl<'r> Real<'r> {
                 bind_group_layouts: &[&global_bgl, &local_bgl],
                 push_constant_ranges: &[],
             });
-            let primitive = wgpu::PrimitiveState {
-                cull_mode: if config.cull_back_faces {
-                    Some(wgpu::Face::Back)
-                } else {
-                    None
-                },
-                ..Default::default()
-            };
+            let primitive = wgpu::PrimitiveState::from(config.primitive);
 
             let mut sample_count = 1;
             let targets = &renderer
@@ -259,7 +254,9 @@ impl<'r> Real<'r> {
 
                     Some(wgpu::ColorTargetState {
                         format: target.format(),
-                        blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        blend: Some(
+                            renderer.blend_state(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        ),
                         write_mask: wgpu::ColorWrites::all(),
                     })
                 })
@@ -284,13 +281,7 @@ impl<'r> Real<'r> {
                         entry_point: "main_vs",
                     },
                     primitive,
-                    depth_stencil: Some(wgpu::DepthStencilState {
-                        format: DEPTH_FORMAT,
-                        depth_compare: wgpu::CompareFunction::LessEqual,
-                        depth_write_enabled: true,
-                        bias: Default::default(),
-                        stencil: Default::default(),
-                    }),
+                    depth_stencil: Some(config.depth.state(DEPTH_FORMAT)),
                     multisample,
                     fragment: Some(wgpu::FragmentState {
                         targets,
@@ -324,7 +315,7 @@ impl<'r> Real<'r> {
 
         Self {
             renderer,
-            depth_texture: None,
+            depth: config.depth,
             global_uniform_buf,
             light_capacity: config.max_lights,
             light_buf,
@@ -367,25 +358,8 @@ impl<'r> crate::RenderPass for Real<'r> {
 
                 let target = target.unwrap();
 
-                let reset_depth = match self.depth_texture {
-                    Some((_, size)) => size != target.size().to_wgpu_size(),
-                    None => true,
-                };
-
-                if reset_depth {
-                    let texture = device.create_texture(&wgpu::TextureDescriptor {
-                        label: Some("depth"),
-                        dimension: wgpu::TextureDimension::D2,
-                        format: DEPTH_FORMAT,
-                        size: target.size().to_wgpu_size(),
-                        sample_count: 1,
-                        mip_level_count: 1,
-                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                        view_formats: &[DEPTH_FORMAT],
-                    });
-                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-                    self.depth_texture = Some((view, target.size().to_wgpu_size()));
-                }
+                let (depth_view, new_depth_buffer) =
+                    renderer.depth_buffer(target.id(), target.size().to_wgpu_size());
 
                 let transforms = scene.calculate_global_transforms();
                 self.uniform_pool.reset();
@@ -497,32 +471,52 @@ impl<'r> crate::RenderPass for Real<'r> {
                     });
                 }
 
-                let frame = target.next_frame()?;
+                let frame = if let Some(frame) = next_frame(target)? {
+                    frame
+                } else {
+                    continue;
+                };
 
                 let mut encoder =
                     device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-                {
+                let timed_pass = renderer.time_pass(format_args!("real: {:?}", target.id()));
+                push_debug_group(
+                    renderer,
+                    &mut encoder,
+                    format_args!("real: {:?}", target.id()),
+                );
+                'draw: {
                     let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: Some("real"),
                         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                             view: &frame.view,
                             resolve_target: None,
                             ops: wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(camera_target.clear_color.into()),
+                                load: wgpu::LoadOp::Clear(
+                                    camera_target.clear_color.to_wgpu_color(target.format()),
+                                ),
                                 store: wgpu::StoreOp::Store,
                             },
                         })],
                         depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.depth_texture.as_ref().unwrap().0,
+                            view: &depth_view,
                             depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(1.0),
+                                load: self
+                                    .depth
+                                    .load_op(new_depth_buffer, camera_target.load_depth),
                                 store: wgpu::StoreOp::Store,
                             }),
                             stencil_ops: None,
                         }),
+                        timestamp_writes: timed_pass.as_ref().map(TimedPass::writes),
                         ..Default::default()
                     });
 
+                    if !camera_target.apply_viewport(&mut pass, target.size())
+                        || !camera_target.apply_scissor(&mut pass, target.size())
+                    {
+                        break 'draw;
+                    }
                     pass.set_pipeline(&self.pipelines.main);
                     pass.set_bind_group(0, &self.global_bind_group, &[]);
 
@@ -553,6 +547,7 @@ impl<'r> crate::RenderPass for Real<'r> {
                     }
                 }
 
+                pop_debug_group(renderer, &mut encoder);
                 commands.append(&mut vec![encoder.finish()]);
                 target.prepare_render(renderer, &mut commands);
 
@@ -2,9 +2,10 @@ use crate::{
     components,
     math::geometry::{Position, Vertex},
     renderer::{
-        renderpass::buffer,
+        renderpass::{buffer, next_frame, pop_debug_group, push_debug_group, DEPTH_FORMAT},
         target::{Dimensions, IsRenderTarget, RenderTargetCollection},
-        RenderContext, RenderPass, RenderPassResult, Renderer,
+        DepthOptions, PrimitiveOptions, RenderContext, RenderPass, RenderPassResult, Renderer,
+        TimedPass,
     },
     scene::SceneState,
     Color,
@@ -13,8 +14,6 @@ use bytemuck::{Pod, Zeroable};
 use fxhash::FxHashMap;
 use std::{mem, sync::RwLockReadGuard};
 
-const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24Plus;
-
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone, Copy, Pod, Zeroable)]
 struct Globals {
@@ -37,20 +36,22 @@ struct LocalKey {
 
 #[derive(Debug)]
 pub struct SolidConfig {
-    pub cull_back_faces: bool,
+    pub primitive: PrimitiveOptions,
+    pub depth: DepthOptions,
 }
 
 impl Default for SolidConfig {
     fn default() -> Self {
         Self {
-            cull_back_faces: true,
+            primitive: PrimitiveOptions::default(),
+            depth: DepthOptions::default(),
         }
     }
 }
 
 pub(crate) struct Solid<'r> {
     renderer: &'r Renderer,
-    depth_texture: Option<(wgpu::TextureView, wgpu::Extent3d)>,
+    depth: DepthOptions,
     global_uniform_buf: wgpu::Buffer,
     global_bind_group: wgpu::BindGroup,
     local_bind_group_layout: wgpu::BindGroupLayout,
@@ -124,7 +125,9 @@ impl<'r> Solid<'r> {
             .map(|target| {
                 Some(wgpu::ColorTargetState {
                     format: target.format(),
-                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    blend: Some(
+                        renderer.blend_state(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    ),
                     write_mask: wgpu::ColorWrites::all(),
                 })
             })
@@ -138,21 +141,8 @@ impl<'r> Solid<'r> {
                 module: &shader_module,
                 entry_point: "main_vs",
             },
-            primitive: wgpu::PrimitiveState {
-                cull_mode: if config.cull_back_faces {
-                    Some(wgpu::Face::Back)
-                } else {
-                    None
-                },
-                ..Default::default()
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: DEPTH_FORMAT,
-                depth_compare: wgpu::CompareFunction::LessEqual,
-                depth_write_enabled: true,
-                bias: Default::default(),
-                stencil: Default::default(),
-            }),
+            primitive: config.primitive.into(),
+            depth_stencil: Some(config.depth.state(DEPTH_FORMAT)),
             multisample: wgpu::MultisampleState::default(),
             fragment: Some(wgpu::FragmentState {
                 targets,
@@ -164,7 +154,7 @@ impl<'r> Solid<'r> {
 
         Self {
             renderer,
-            depth_texture: None,
+            depth: config.depth,
             global_uniform_buf,
             global_bind_group,
             local_bind_group_layout: local_bgl,
@@ -204,26 +194,8 @@ impl<'r> RenderPass for Solid<'r> {
 
                 let target = target.unwrap();
 
-                let reset_depth = match self.depth_texture {
-                    Some((_, size)) => size != target.size().to_wgpu_size(),
-                    None => true,
-                };
-                if reset_depth {
-                    // @TODO this should not happen here. Our Texture
-                    //       implementation contains a method to do this.
-                    let texture = device.create_texture(&wgpu::TextureDescriptor {
-                        label: Some("depth"),
-                        dimension: wgpu::TextureDimension::D2,
-                        format: DEPTH_FORMAT,
-                        size: target.size().to_wgpu_size(),
-                        sample_count: 1,
-                        mip_level_count: 1,
-                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                        view_formats: &[DEPTH_FORMAT],
-                    });
-                    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-                    self.depth_texture = Some((view, target.size().to_wgpu_size()));
-                }
+                let (depth_view, new_depth_buffer) =
+                    renderer.depth_buffer(target.id(), target.size().to_wgpu_size());
 
                 let transforms = scene.calculate_global_transforms();
                 self.uniform_pool.reset();
@@ -267,12 +239,22 @@ impl<'r> RenderPass for Solid<'r> {
                     });
                 }
 
-                let frame = target.next_frame()?;
+                let frame = if let Some(frame) = next_frame(target)? {
+                    frame
+                } else {
+                    continue;
+                };
 
                 let mut encoder =
                     device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
-
-                {
+                let timed_pass = renderer.time_pass(format_args!("solid: {:?}", target.id()));
+                push_debug_group(
+                    renderer,
+                    &mut encoder,
+                    format_args!("solid: {:?}", target.id()),
+                );
+
+                'draw: {
                     let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                         label: Some("solid"),
 
@@ -284,20 +266,30 @@ impl<'r> RenderPass for Solid<'r> {
                             ops: wgpu::Operations {
                                 // @TODO this should be a property of the target,
                                 //       instead of the camera.
-                                load: wgpu::LoadOp::Clear(camera_target.clear_color.into()),
+                                load: wgpu::LoadOp::Clear(
+                                    camera_target.clear_color.to_wgpu_color(target.format()),
+                                ),
                                 store: wgpu::StoreOp::Store,
                             },
                         })],
                         depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                            view: &self.depth_texture.as_ref().unwrap().0,
+                            view: &depth_view,
                             depth_ops: Some(wgpu::Operations {
-                                load: wgpu::LoadOp::Clear(1.0),
+                                load: self
+                                    .depth
+                                    .load_op(new_depth_buffer, camera_target.load_depth),
                                 store: wgpu::StoreOp::Store,
                             }),
                             stencil_ops: None,
                         }),
+                        timestamp_writes: timed_pass.as_ref().map(TimedPass::writes),
                         ..Default::default()
                     });
+                    if !camera_target.apply_viewport(&mut pass, target.size())
+                        || !camera_target.apply_scissor(&mut pass, target.size())
+                    {
+                        break 'draw;
+                    }
                     pass.set_pipeline(&self.pipeline);
                     pass.set_bind_group(0, &self.global_bind_group, &[]);
 
@@ -338,6 +330,7 @@ impl<'r> RenderPass for Solid<'r> {
                     }
                 }
 
+                pop_debug_group(renderer, &mut encoder);
                 commands.append(&mut vec![encoder.finish()]);
                 target.prepare_render(renderer, &mut commands);
 
@@ -1,8 +1,10 @@
 use crate::{
     components::{IsHidden, Shape, Sprite},
     renderer::{
-        renderpass::buffer, target::Dimensions, IsRenderTarget, RenderContext, RenderPass,
-        RenderPassResult, RenderTargetCollection, Renderer,
+        renderpass::{buffer, next_frame, pop_debug_group, push_debug_group},
+        target::Dimensions,
+        IsRenderTarget, RenderContext, RenderPass, RenderPassResult, RenderTargetCollection,
+        Renderer, TimedPass,
     },
     scene::SceneState,
     Shader,
@@ -211,7 +213,7 @@ impl<'r> Toy<'r> {
 
                     Some(wgpu::ColorTargetState {
                         format: target.format(),
-                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        blend: Some(renderer.blend_state(wgpu::BlendState::ALPHA_BLENDING)),
                         write_mask: wgpu::ColorWrites::all(),
                     })
                 })
@@ -277,6 +279,7 @@ impl<'r> RenderPass for Toy<'r> {
 
         for (camera_id, camera) in scene.cameras().iter() {
             let camera_targets = scene.get_camera_targets(camera_id);
+            let mut dropped_frame = false;
 
             for camera_target in camera_targets {
                 let target = targets.get(&camera_target.target_id);
@@ -392,6 +395,15 @@ impl<'r> RenderPass for Toy<'r> {
 
                     let image = if let Some(image) = resources.get_texture(&texture_id) {
                         image
+                    } else if let Some(placeholder) =
+                        resources.get_texture(&renderer.placeholder_texture_id())
+                    {
+                        log::warn!(
+                            "Object {:?} is using a non-existent Texture (Id: {:?}). Drawing a placeholder...",
+                            object_id,
+                            texture_id
+                        );
+                        placeholder
                     } else {
                         log::error!(
                             "Object {:?} is using a non-existent Texture (Id: {:?})",
@@ -464,18 +476,48 @@ impl<'r> RenderPass for Toy<'r> {
                 self.temp
                     .sort_by_key(|s| (s.camera_distance * -1000.0) as i64);
 
-                let frame = target.next_frame()?;
+                let frame = if let Some(frame) = next_frame(target)? {
+                    frame
+                } else {
+                    dropped_frame = true;
+                    continue;
+                };
                 frames_to_render.push((target, frame, camera_target));
             }
 
+            // The pass attaches all of the camera's targets, matching the
+            // pipeline's color targets, so a dropped frame skips the camera.
+            if dropped_frame || frames_to_render.is_empty() {
+                self.temp.clear();
+                frames_to_render.clear();
+                continue;
+            }
+
+            // All of the camera's targets share this pass, so they also share a viewport
+            let (first_target, _, first_description) = &frames_to_render[0];
+            let shares_viewport = frames_to_render.iter().all(|(_, _, camera_target)| {
+                camera_target.viewport == first_description.viewport
+                    && camera_target.scissor == first_description.scissor
+            });
+            if !shares_viewport {
+                log::error!(
+                    "Camera {:?} renders to targets with different viewports or scissor rects.
+                    The Toy RenderPass draws to all of a camera's targets at once,
+                    so they must share the same viewport and scissor rect. Skipping draws...",
+                    camera_id
+                );
+            }
+
             let color_attachments = frames_to_render
                 .iter()
-                .map(|(_, frame, camera_target)| {
+                .map(|(target, frame, camera_target)| {
                     Some(wgpu::RenderPassColorAttachment {
                         view: &frame.view,
                         resolve_target: None,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(camera_target.clear_color.into()),
+                            load: wgpu::LoadOp::Clear(
+                                camera_target.clear_color.to_wgpu_color(target.format()),
+                            ),
                             store: wgpu::StoreOp::Store,
                         },
                     })
@@ -484,17 +526,32 @@ impl<'r> RenderPass for Toy<'r> {
 
             let mut encoder =
                 device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            let timed_pass = renderer.time_pass(format_args!("toy: camera {:?}", camera_id));
+            push_debug_group(
+                renderer,
+                &mut encoder,
+                format_args!("toy: camera {:?}", camera_id),
+            );
 
             // @TODO this is the core of what the RenderPass does, and it only needs a Frame
             //       from a specific target. The RenderPass trait abstraction for multiple targets
             //       is wrong, I should go back to the older method or craate a second trait for a single target.
-            {
+            'draw: {
                 let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     label: Some("Toy Render Pass"),
                     color_attachments: color_attachments.as_slice(),
                     depth_stencil_attachment: None,
+                    timestamp_writes: timed_pass.as_ref().map(TimedPass::writes),
                     ..Default::default()
                 });
+
+                if !shares_viewport
+                    || !first_description.apply_viewport(&mut pass, first_target.size())
+                    || !first_description.apply_scissor(&mut pass, first_target.size())
+                {
+                    break 'draw;
+                }
+
                 pass.set_pipeline(&self.pipelines.transparent);
                 pass.set_bind_group(0, &self.globals_bind_group, &[]);
 
@@ -510,7 +567,10 @@ impl<'r> RenderPass for Toy<'r> {
                     pass.draw(0..4, 0..1); // @TODO this should be indexed
                 }
             }
+            // Instances are left over if the draws were skipped
+            self.temp.clear();
 
+            pop_debug_group(renderer, &mut encoder);
             commands.append(&mut vec![encoder.finish()]);
 
             for (target, frame, _) in frames_to_render.drain(..) {
@@ -522,3 +582,98 @@ impl<'r> RenderPass for Toy<'r> {
         Ok((commands, rendered_frames))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Circle, CircleOptions, Color},
+        renderer::target::RenderTargetDescription,
+        FragmentColor, Quad, Scene,
+    };
+
+    #[test]
+    fn half_width_viewport_leaves_the_other_half_clear() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let size = Quad::from_size(100, 100);
+        let mut description = RenderTargetDescription::create_texture_target(size).unwrap();
+        description.clear_color = Color(0x0000ffff);
+        description.set_viewport(Quad::from_region(0, 0, 50, 100));
+        let target_id = description.target_id;
+
+        // Covers the whole target
+        let mut scene = Scene::new_unregistered();
+        let mut circle = Circle::new(CircleOptions {
+            radius: 200.0,
+            color: Color(0xff0000ff),
+            border: 0.0,
+        });
+        scene.add(&mut circle);
+        scene.target(&description);
+
+        let renderer = FragmentColor::renderer();
+        renderer.try_read().unwrap().render(&scene).unwrap();
+        let bytes = target_id.read_region_blocking(size).unwrap();
+        renderer
+            .try_read()
+            .unwrap()
+            .remove_target(&target_id)
+            .unwrap();
+
+        let clear = [0x00, 0x00, 0xff, 0xff];
+        let pixel = |x: usize, y: usize| &bytes[(y * 100 + x) * 4..][..4];
+        for y in 0..100 {
+            for x in 50..100 {
+                assert_eq!(pixel(x, y), clear, "pixel {}x{} was drawn", x, y);
+            }
+        }
+        assert_ne!(pixel(25, 50), clear);
+    }
+
+    #[test]
+    fn scissor_clips_without_scaling_the_draws() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let size = Quad::from_size(100, 100);
+        let mut description = RenderTargetDescription::create_texture_target(size).unwrap();
+        description.clear_color = Color(0x0000ffff);
+        description.set_scissor(Quad::from_region(50, 0, 100, 100));
+        let target_id = description.target_id;
+
+        // Covers the whole target
+        let mut scene = Scene::new_unregistered();
+        let mut circle = Circle::new(CircleOptions {
+            radius: 200.0,
+            color: Color(0xff0000ff),
+            border: 0.0,
+        });
+        scene.add(&mut circle);
+        scene.target(&description);
+
+        let renderer = FragmentColor::renderer();
+        renderer.try_read().unwrap().render(&scene).unwrap();
+        let bytes = target_id.read_region_blocking(size).unwrap();
+        renderer
+            .try_read()
+            .unwrap()
+            .remove_target(&target_id)
+            .unwrap();
+
+        let clear = [0x00, 0x00, 0xff, 0xff];
+        let pixel = |x: usize, y: usize| &bytes[(y * 100 + x) * 4..][..4];
+        for y in 0..100 {
+            for x in 0..50 {
+                assert_eq!(pixel(x, y), clear, "pixel {}x{} was drawn", x, y);
+            }
+        }
+        assert_ne!(pixel(75, 50), clear);
+    }
+}
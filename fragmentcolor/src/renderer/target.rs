@@ -6,13 +6,12 @@ use crate::{
     math::geometry::Quad,
     renderer::{Commands, Renderer},
     resources::{
-        buffer::{Buffer, BufferSize, TextureBuffer},
+        buffer::{Buffer, BufferSize, Readback, TextureBuffer},
         texture::{Texture, TextureId},
     },
     scene::{Object, ObjectId},
     FragmentColor, SceneObject,
 };
-use image::Rgba;
 use std::{
     collections::{
         hash_map::{Values, ValuesMut},
@@ -26,6 +25,20 @@ use winit::window::WindowId;
 type Error = Box<dyn std::error::Error>;
 pub(crate) type RenderedFrames = Vec<(TargetId, Frame)>;
 
+/// Encodes tightly packed RGBA8 bytes as a PNG file in memory
+pub(crate) fn encode_png(bytes: Vec<u8>, size: Quad) -> Result<Vec<u8>, Error> {
+    let image = image::RgbaImage::from_raw(size.width(), size.height(), bytes)
+        .ok_or("Image bytes do not match the image size")?;
+
+    let mut png = Vec::new();
+    image.write_to(
+        &mut std::io::Cursor::new(&mut png),
+        image::ImageOutputFormat::Png,
+    )?;
+
+    Ok(png)
+}
+
 pub trait Dimensions {
     fn size(&self) -> Quad;
     fn aspect(&self) -> f32;
@@ -98,6 +111,28 @@ pub struct RenderTargetDescription {
     /// On Web, this draws a transparent canvas.
     pub clear_color: components::Color,
 
+    /// The region of the target to render to.
+    ///
+    /// Defaults to None, which renders to the full target. The Toy
+    /// RenderPass draws all targets of a camera at once, so they must
+    /// share the same viewport there.
+    pub viewport: Option<Viewport>,
+
+    /// The region of the target where pixels can be written, in pixels.
+    ///
+    /// Unlike the viewport, the scissor clips the draws without
+    /// scaling them. Defaults to None, which allows the full target.
+    /// Like viewports, the Toy RenderPass requires the targets of
+    /// a camera to share the same scissor rect.
+    pub scissor: Option<Quad>,
+
+    /// Whether the 3D RenderPasses keep the depth of the previous render.
+    ///
+    /// Defaults to false, which uses the `clear` value of the Renderer's
+    /// DepthOptions. Useful to test against the depth of another Scene
+    /// rendered to the same target, like a depth prepass.
+    pub load_depth: bool,
+
     /// Callback function to run right before rendering.
     ///
     /// This is useful for updating uniforms, and syncing
@@ -110,6 +145,51 @@ pub struct RenderTargetDescription {
     pub after_render: Option<Callback<Vec<u8>>>,
 }
 
+/// A region of a Render Target, in pixels, and its depth range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub region: Quad,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+impl Viewport {
+    /// Creates a Viewport with the default depth range (0.0 to 1.0)
+    pub fn new(region: Quad) -> Self {
+        Self {
+            region,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        }
+    }
+
+    /// Clips the Viewport to the target size.
+    ///
+    /// Returns None if nothing of the Viewport is inside the target.
+    pub(crate) fn clamped(&self, target_size: Quad) -> Option<Self> {
+        let mut region = self.region;
+        region.clamp(target_size.width(), target_size.height());
+
+        if region.area() == 0 {
+            return None;
+        }
+
+        Some(Self { region, ..*self })
+    }
+
+    /// Restricts the draws of a render pass to this Viewport.
+    pub(crate) fn apply(&self, pass: &mut wgpu::RenderPass<'_>) {
+        pass.set_viewport(
+            self.region.min_x as f32,
+            self.region.min_y as f32,
+            self.region.width_f32(),
+            self.region.height_f32(),
+            self.min_depth,
+            self.max_depth,
+        );
+    }
+}
+
 /// Allow a TargetDescription to describe itself so it can
 /// be used as a manual configuration for a RenderTarget
 /// if the user wants to have t
@@ -127,6 +207,9 @@ impl RenderTargetDescription {
             target_size,
             camera_id: None,
             clear_color: components::Color::default(),
+            viewport: None,
+            scissor: None,
+            load_depth: false,
             before_render: None,
             after_render: None,
         }
@@ -162,6 +245,26 @@ impl RenderTargetDescription {
         Ok(Self::new(target_id, size))
     }
 
+    /// Creates a texture target with a custom color format.
+    ///
+    /// Useful for HDR rendering with `Rgba16Float`. The format is validated
+    /// against the adapter at creation: it must be renderable, blendable
+    /// and filterable. `Rgba32Float` is usually neither of the last two.
+    pub fn create_texture_target_with_format(
+        size: Quad,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, Error> {
+        let texture = Texture::create_destination_texture_with_format(size.to_wgpu_size(), format)?;
+
+        let target_id = if let Ok(renderer) = FragmentColor::renderer().try_read() {
+            renderer.add_texture_target(texture)?
+        } else {
+            return Err("Renderer is not available".into());
+        };
+
+        Ok(Self::new(target_id, size))
+    }
+
     pub fn try_set_camera(&mut self, camera: &Object<Camera>) -> Result<&mut Self, Error> {
         let camera_id = if let Some(camera_id) = camera.id() {
             camera_id
@@ -181,6 +284,123 @@ impl RenderTargetDescription {
         self
     }
 
+    /// Sets the clear color from a CSS string.
+    ///
+    /// Accepts named colors, hex (#rgb, #rgba, #rrggbb, #rrggbbaa)
+    /// and functional notations like `rgba(255, 136, 0, 0.8)`.
+    pub fn set_clear_color_css(&mut self, css: &str) -> Result<&mut Self, Error> {
+        self.clear_color = components::Color::from_css(css)?;
+        Ok(self)
+    }
+
+    /// Restricts rendering to a region of the target, in pixels.
+    pub fn set_viewport(&mut self, region: Quad) -> &mut Self {
+        self.viewport = Some(Viewport::new(region));
+        self
+    }
+
+    /// Restricts rendering to a region of the target, with a custom depth range.
+    ///
+    /// Both depths must be within 0.0 and 1.0, and min_depth must be
+    /// smaller than max_depth. Useful to draw a HUD at a fixed depth.
+    pub fn set_viewport_with_depth(
+        &mut self,
+        region: Quad,
+        min_depth: f32,
+        max_depth: f32,
+    ) -> Result<&mut Self, Error> {
+        if !(0.0..=1.0).contains(&min_depth)
+            || !(0.0..=1.0).contains(&max_depth)
+            || min_depth >= max_depth
+        {
+            return Err(format!(
+                "Invalid viewport depth range {}..{}. Expected 0.0 <= min < max <= 1.0",
+                min_depth, max_depth
+            )
+            .into());
+        }
+
+        self.viewport = Some(Viewport {
+            region,
+            min_depth,
+            max_depth,
+        });
+        Ok(self)
+    }
+
+    /// Renders to the full target again.
+    pub fn clear_viewport(&mut self) -> &mut Self {
+        self.viewport = None;
+        self
+    }
+
+    /// Applies the viewport of this target to a render pass.
+    ///
+    /// Returns false if the viewport is outside of the target,
+    /// in which case there is nothing to draw.
+    pub(crate) fn apply_viewport(
+        &self,
+        pass: &mut wgpu::RenderPass<'_>,
+        target_size: Quad,
+    ) -> bool {
+        match self.viewport {
+            Some(viewport) => match viewport.clamped(target_size) {
+                Some(viewport) => {
+                    viewport.apply(pass);
+                    true
+                }
+                None => false,
+            },
+            None => true,
+        }
+    }
+
+    /// Clips rendering to a region of the target, in pixels.
+    pub fn set_scissor(&mut self, region: Quad) -> &mut Self {
+        self.scissor = Some(region);
+        self
+    }
+
+    /// Allows rendering to the full target again.
+    pub fn clear_scissor(&mut self) -> &mut Self {
+        self.scissor = None;
+        self
+    }
+
+    /// Keeps the depth of the previous render to this target.
+    pub fn load_depth(&mut self) -> &mut Self {
+        self.load_depth = true;
+        self
+    }
+
+    /// Clears the depth of this target with the Renderer's DepthOptions again.
+    pub fn clear_depth(&mut self) -> &mut Self {
+        self.load_depth = false;
+        self
+    }
+
+    /// Applies the scissor rect of this target to a render pass.
+    ///
+    /// The scissor rect is clipped to the target size. Returns false if
+    /// it is outside of the target, in which case there is nothing to draw.
+    pub(crate) fn apply_scissor(&self, pass: &mut wgpu::RenderPass<'_>, target_size: Quad) -> bool {
+        match self.scissor {
+            Some(scissor) => match scissor.intersect(target_size) {
+                Some(region) => {
+                    pass.set_scissor_rect(
+                        region.min_x,
+                        region.min_y,
+                        region.width(),
+                        region.height(),
+                    );
+                    true
+                }
+                None => false,
+            },
+            None => true,
+        }
+    }
+
     pub fn before_render(&mut self, callback: impl CallbackFn<()> + 'static) -> &mut Self {
         self.before_render = Some(Arc::new(RwLock::new(callback)));
         self
@@ -216,6 +436,50 @@ pub enum TargetId {
     Window(WindowId),
 }
 
+impl TargetId {
+    /// Reads back a region of the last frame rendered to this Texture target.
+    ///
+    /// The region is clamped to the target size, and the bytes are tightly
+    /// packed rows in the target's format. The future polls the device
+    /// itself without blocking, so any executor can drive it, and it
+    /// doesn't hold the Renderer while waiting for the GPU.
+    pub async fn read_region(&self, region: Quad) -> Result<Vec<u8>, Error> {
+        self.read_region_with(region, wgpu::Maintain::Poll).await
+    }
+
+    /// Same as `read_region()`, blocking until the GPU finishes.
+    ///
+    /// Not available on the Web, where the browser drives the GPU.
+    #[cfg(not(wasm))]
+    pub fn read_region_blocking(&self, region: Quad) -> Result<Vec<u8>, Error> {
+        pollster::block_on(self.read_region_with(region, wgpu::Maintain::Wait))
+    }
+
+    async fn read_region_with(
+        &self,
+        region: Quad,
+        maintain: wgpu::Maintain,
+    ) -> Result<Vec<u8>, Error> {
+        // The guard is dropped before awaiting the GPU
+        let readback = {
+            let renderer = FragmentColor::renderer();
+            let renderer = if let Ok(renderer) = renderer.try_read() {
+                renderer
+            } else {
+                return Err("Renderer is locked. Cannot read rendered region!".into());
+            };
+
+            renderer.target_region_readback(self, region)?
+        };
+
+        if let Some(readback) = readback {
+            readback.read(maintain).await
+        } else {
+            Ok(Vec::new())
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum RenderTarget {
     Texture(TextureTarget),
@@ -335,8 +599,12 @@ impl IsRenderTarget for RenderTarget {
 
     fn resize(&mut self, renderer: &Renderer, size: wgpu::Extent3d) -> Result<(), Error> {
         match self {
-            Self::Texture(_) => {
-                let new_target = TextureTarget::new(renderer, size)?;
+            Self::Texture(target) => {
+                let format = target.texture.format;
+                let mut texture = Texture::create_destination_texture_with_format(size, format)?;
+                // The Targets Database and Scene descriptions are keyed by this id
+                texture.id = target.texture.id;
+                let new_target = TextureTarget::from_texture(renderer, texture)?;
                 *self = RenderTarget::Texture(new_target);
             }
             Self::Window(window) => window.resize(renderer, size),
@@ -402,11 +670,12 @@ impl TextureTarget {
         let size = texture.size;
         Self::validate(renderer, size)?;
 
-        let buffer_size = BufferSize::new(size.width as usize, size.height as usize);
+        let buffer_size =
+            BufferSize::from_format(size.width as usize, size.height as usize, texture.format);
         let buffer = renderer.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Render target buffer"),
             size: buffer_size.size(),
-            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
         });
 
@@ -485,51 +754,180 @@ impl TextureTarget {
         }
     }
 
-    // @TODO call this at some point.
-    #[allow(dead_code)]
-    pub async fn get_rendered_frame_bytes(&self, renderer: &Renderer) -> Result<Vec<u8>, Error> {
-        if let Some(texture_buffer) = &self.buffer {
-            let output_buffer = &texture_buffer.inner.buffer;
-
-            // We need to scope the mapping variables so that we can unmap the buffer
-            let rendered_image = {
-                let buffer_slice = output_buffer.slice(..);
-
-                // NOTE: We have to create the mapping THEN device.poll()
-                // before await the future. Otherwise the application will freeze.
-                let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
-
-                buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
-                    sender.send(result).unwrap();
-                });
-
-                renderer.device.poll(wgpu::Maintain::Wait);
-
-                let output_buffer_data = if let Some(received) = receiver.receive().await {
-                    if received.is_ok() {
-                        buffer_slice.get_mapped_range()
-                    } else {
-                        return Err("Failed to map texture buffer".into());
-                    }
-                } else {
-                    return Err("Failed to read texture buffer".into());
-                };
-
-                let buffer = image::ImageBuffer::<Rgba<u8>, _>::from_raw(
-                    self.texture.size.width,
-                    self.texture.size.height,
-                    output_buffer_data,
-                )
-                .unwrap();
+    /// Copies a rectangular region of the rendered texture to a new staging buffer.
+    ///
+    /// Only the requested region is copied from the GPU. The region is
+    /// clamped to the texture size, and the bytes read back from the
+    /// staging buffer are tightly packed rows in the texture's format.
+    /// Returns None for an empty region.
+    pub fn region_readback(&self, renderer: &Renderer, region: Quad) -> Option<Readback> {
+        let mut region = region;
+        region.clamp(self.texture.size.width, self.texture.size.height);
+        if region.area() == 0 {
+            return None;
+        }
 
-                buffer.to_vec()
-            };
+        let size = BufferSize::from_format(
+            region.width() as usize,
+            region.height() as usize,
+            self.texture.format,
+        );
+        let readback = Readback::new(&renderer.device, size);
+
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render target region transfer encoder"),
+            });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture.data,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.min_x,
+                    y: region.min_y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: readback.buffer(),
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(readback.size().padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            region.to_wgpu_size(),
+        );
 
-            output_buffer.unmap();
+        renderer.queue.submit(Some(encoder.finish()));
+
+        Some(readback)
+    }
 
-            Ok(rendered_image)
+    /// Copies the last frame in this target's buffer to a new staging buffer.
+    ///
+    /// The bytes read back are tightly packed rows in the texture's format,
+    /// i.e. 8 bytes per pixel of half floats for `Rgba16Float`. For sRGB
+    /// targets the bytes are sRGB-encoded, matching CSS and PNG conventions.
+    pub fn frame_readback(&self, renderer: &Renderer) -> Result<Readback, Error> {
+        let texture_buffer = if let Some(texture_buffer) = &self.buffer {
+            texture_buffer
         } else {
-            Err("No texture buffer available to copy from".into())
+            return Err("No texture buffer available to copy from".into());
+        };
+
+        let Buffer { buffer, size } = &texture_buffer.inner;
+        let readback = Readback::new(&renderer.device, size.clone());
+
+        let mut encoder = renderer
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render target frame transfer encoder"),
+            });
+        encoder.copy_buffer_to_buffer(buffer, 0, readback.buffer(), 0, size.size());
+        renderer.queue.submit(Some(encoder.finish()));
+
+        Ok(readback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{Circle, CircleOptions, Color},
+        renderer::RenderContext,
+        Scene,
+    };
+
+    #[test]
+    fn viewport_is_clamped_to_the_target() {
+        let target_size = Quad::from_size(100, 100);
+
+        let half = Viewport::new(Quad::from_region(0, 0, 50, 100));
+        assert_eq!(half.clamped(target_size), Some(half));
+
+        let overflowing = Viewport::new(Quad::from_region(50, 50, 100, 100));
+        let clamped = overflowing.clamped(target_size).unwrap();
+        assert_eq!(clamped.region, Quad::from_region(50, 50, 50, 50));
+
+        let outside = Viewport::new(Quad::from_region(200, 200, 10, 10));
+        assert_eq!(outside.clamped(target_size), None);
+    }
+
+    #[test]
+    fn resizing_a_texture_target_keeps_its_id() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let description =
+            RenderTargetDescription::create_texture_target(Quad::from_size(100, 100)).unwrap();
+        let target_id = description.target_id;
+
+        let renderer = FragmentColor::renderer();
+        let renderer = renderer.try_read().unwrap();
+        {
+            let mut targets = renderer.write_targets().unwrap();
+            let target = targets.get_mut(&target_id).unwrap();
+            target
+                .resize(&renderer, Quad::from_size(50, 20).to_wgpu_size())
+                .unwrap();
         }
+
+        let targets = renderer.read_targets().unwrap();
+        let target = targets.get(&target_id).expect("Resized target not found");
+        assert_eq!(target.id(), target_id);
+        assert_eq!(target.size(), Quad::from_size(50, 20));
+        drop(targets);
+
+        renderer.remove_target(&target_id).unwrap();
+    }
+
+    #[test]
+    fn async_readback_resolves_under_pollster() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let size = Quad::from_size(16, 16);
+        let mut description = RenderTargetDescription::create_texture_target(size).unwrap();
+        description.clear_color = Color(0x0000ffff);
+        let target_id = description.target_id;
+
+        // Covers the whole target
+        let mut scene = Scene::new_unregistered();
+        let mut circle = Circle::new(CircleOptions {
+            radius: 200.0,
+            color: Color(0xff0000ff),
+            border: 0.0,
+        });
+        scene.add(&mut circle);
+        scene.target(&description);
+        FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .render(&scene)
+            .unwrap();
+
+        // Nothing else polls the device: the future must drive it itself,
+        // and must not hold the Renderer while it waits.
+        let bytes = pollster::block_on(target_id.read_region(size)).unwrap();
+        let renderer = FragmentColor::renderer();
+        renderer
+            .try_read()
+            .unwrap()
+            .remove_target(&target_id)
+            .unwrap();
+
+        assert_eq!(bytes.len(), 16 * 16 * 4);
+        assert_ne!(&bytes[(8 * 16 + 8) * 4..][..4], &[0x00, 0x00, 0xff, 0xff]);
     }
 }
@@ -0,0 +1,247 @@
+use std::sync::{Arc, Mutex};
+
+/// How many passes can be timed in a single frame.
+const MAX_TIMED_PASSES: u32 = 64;
+
+/// Size of a resolved timestamp, in bytes.
+const TIMESTAMP_SIZE: u64 = std::mem::size_of::<u64>() as u64;
+
+/// Measures how long each RenderPass takes on the GPU.
+///
+/// Passes write a timestamp at their beginning and end into a query set.
+/// When the frame is submitted, the timestamps are resolved and read back
+/// asynchronously, so the timings show up after the GPU finishes the frame.
+#[derive(Debug)]
+pub(crate) struct FrameTimer {
+    query_set: Arc<wgpu::QuerySet>,
+    resolve_buffer: wgpu::Buffer,
+    labels: Mutex<Vec<String>>,
+    timings: Arc<Mutex<Vec<(String, f64)>>>,
+    period: f32, // nanoseconds per timestamp tick
+}
+
+/// A pair of query slots reserved for one RenderPass.
+pub(crate) struct TimedPass {
+    query_set: Arc<wgpu::QuerySet>,
+    index: u32,
+}
+
+impl TimedPass {
+    /// Writes the beginning and end timestamps of a RenderPass.
+    pub(crate) fn writes(&self) -> wgpu::RenderPassTimestampWrites<'_> {
+        wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(self.index),
+            end_of_pass_write_index: Some(self.index + 1),
+        }
+    }
+}
+
+impl FrameTimer {
+    /// Returns None if the device does not support timestamp queries.
+    pub(crate) fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timer Queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: MAX_TIMED_PASSES * 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timer Resolve Buffer"),
+            size: MAX_TIMED_PASSES as u64 * 2 * TIMESTAMP_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set: Arc::new(query_set),
+            resolve_buffer,
+            labels: Mutex::new(Vec::new()),
+            timings: Arc::new(Mutex::new(Vec::new())),
+            period: queue.get_timestamp_period(),
+        })
+    }
+
+    /// Reserves the timestamps of a RenderPass in this frame.
+    ///
+    /// Returns None if the frame already timed too many passes.
+    pub(crate) fn time_pass(&self, label: String) -> Option<TimedPass> {
+        let mut labels = self.labels.lock().ok()?;
+        if labels.len() as u32 >= MAX_TIMED_PASSES {
+            return None;
+        }
+
+        let index = labels.len() as u32 * 2;
+        labels.push(label);
+
+        Some(TimedPass {
+            query_set: self.query_set.clone(),
+            index,
+        })
+    }
+
+    /// Records the commands that copy this frame's timestamps to a readable buffer.
+    ///
+    /// Returns None if no pass was timed in this frame.
+    pub(crate) fn resolve(
+        &self,
+        device: &wgpu::Device,
+    ) -> Option<(wgpu::CommandBuffer, FrameTimings)> {
+        let labels = std::mem::take(&mut *self.labels.lock().ok()?);
+        if labels.is_empty() {
+            return None;
+        }
+
+        let size = labels.len() as u64 * 2 * TIMESTAMP_SIZE;
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timer Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Frame Timer Resolve"),
+        });
+        encoder.resolve_query_set(
+            &self.query_set,
+            0..labels.len() as u32 * 2,
+            &self.resolve_buffer,
+            0,
+        );
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &readback, 0, size);
+
+        let timings = FrameTimings {
+            readback: Arc::new(readback),
+            labels,
+            period: self.period,
+            timings: self.timings.clone(),
+        };
+
+        Some((encoder.finish(), timings))
+    }
+
+    /// Timings of the last frame read back from the GPU, in milliseconds.
+    pub(crate) fn last_frame_timings(&self) -> Vec<(String, f64)> {
+        if let Ok(timings) = self.timings.lock() {
+            timings.clone()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// The timestamps of a submitted frame, waiting to be read back.
+pub(crate) struct FrameTimings {
+    readback: Arc<wgpu::Buffer>,
+    labels: Vec<String>,
+    period: f32,
+    timings: Arc<Mutex<Vec<(String, f64)>>>,
+}
+
+impl FrameTimings {
+    /// Maps the readback buffer, and stores the timings once the GPU is done.
+    ///
+    /// Must be called after the frame commands are submitted.
+    /// The mapping callback runs on the next device poll.
+    pub(crate) fn read(self) {
+        let readback = self.readback.clone();
+        readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                if let Err(error) = result {
+                    log::warn!("Failed to read the frame timings: {}", error);
+                    return;
+                }
+
+                let timestamps = {
+                    let data = self.readback.slice(..).get_mapped_range();
+                    bytemuck::cast_slice::<u8, u64>(&data).to_vec()
+                };
+                self.readback.unmap();
+
+                let timings = elapsed_milliseconds(&self.labels, &timestamps, self.period);
+                if let Ok(mut last_frame) = self.timings.lock() {
+                    *last_frame = timings;
+                }
+            });
+    }
+}
+
+/// Pairs each label with the time between its beginning and end timestamps.
+fn elapsed_milliseconds(labels: &[String], timestamps: &[u64], period: f32) -> Vec<(String, f64)> {
+    labels
+        .iter()
+        .zip(timestamps.chunks_exact(2))
+        .map(|(label, pair)| {
+            let ticks = pair[1].saturating_sub(pair[0]);
+            let milliseconds = ticks as f64 * period as f64 / 1_000_000.0;
+            (label.clone(), milliseconds)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::{Circle, CircleOptions, Color},
+        renderer::target::RenderTargetDescription,
+        FragmentColor, Quad, Scene,
+    };
+
+    #[test]
+    fn timed_frames_report_their_passes() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+        if !FragmentColor::enable_timing(true).unwrap() {
+            assert!(FragmentColor::last_frame_timings().is_empty());
+            return;
+        }
+
+        let description =
+            RenderTargetDescription::create_texture_target(Quad::from_size(64, 64)).unwrap();
+        let mut scene = Scene::new_unregistered();
+        let mut circle = Circle::new(CircleOptions {
+            radius: 20.0,
+            color: Color(0xff0000ff),
+            border: 0.0,
+        });
+        scene.add(&mut circle);
+        scene.target(&description);
+
+        let renderer = FragmentColor::renderer();
+        renderer.try_read().unwrap().render(&scene).unwrap();
+        FragmentColor::poll(wgpu::Maintain::Wait).unwrap();
+        let timings = FragmentColor::last_frame_timings();
+
+        FragmentColor::enable_timing(false).unwrap();
+        renderer
+            .try_read()
+            .unwrap()
+            .remove_target(&description.target_id)
+            .unwrap();
+
+        assert!(timings.iter().any(|(label, _)| label.starts_with("toy")));
+        assert!(timings.iter().all(|(_, milliseconds)| *milliseconds >= 0.0));
+    }
+
+    #[test]
+    fn timestamps_are_converted_to_milliseconds() {
+        let labels = vec!["toy".to_string(), "solid".to_string()];
+        let timestamps = [1_000, 3_000_000, 5_000_000, 4_000_000];
+
+        let timings = elapsed_milliseconds(&labels, &timestamps, 2.0);
+
+        assert_eq!(timings[0].0, "toy");
+        assert!((timings[0].1 - 5.998).abs() < 1e-9);
+        // Timestamps that go backwards count as zero
+        assert_eq!(timings[1], ("solid".to_string(), 0.0));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::Quad;
+
+/// Pixels around each image, filled with copies of its edges, so
+/// linear filtering doesn't bleed neighbouring images into each other
+const PADDING: u32 = 1;
+
+/// Packs rectangles of the given sizes into shelves.
+///
+/// Rectangles are sorted by height and placed left to right.
+/// When a row is full, a new shelf starts below the tallest
+/// rectangle of the current one.
+///
+/// Returns the size of the atlas and the region of each
+/// rectangle, in the same order as `sizes`.
+pub(crate) fn pack_shelves(sizes: &[(u32, u32)]) -> (Quad, Vec<Quad>) {
+    let padded = |size: u32| size + PADDING * 2;
+
+    // Large atlases overflow a u32 area
+    let area: u64 = sizes
+        .iter()
+        .map(|&(w, h)| padded(w) as u64 * padded(h) as u64)
+        .sum();
+    let widest = sizes.iter().map(|&(w, _)| padded(w)).max().unwrap_or(0);
+    let atlas_width = widest.max((area as f64).sqrt().ceil() as u32);
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(sizes[index].1));
+
+    let mut regions = vec![Quad::from_size(0, 0); sizes.len()];
+    let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+    let mut used_width = 0;
+
+    for index in order {
+        let (width, height) = sizes[index];
+
+        if x + padded(width) > atlas_width {
+            x = 0;
+            y += shelf_height;
+            shelf_height = 0;
+        }
+
+        regions[index] = Quad::from_region(x + PADDING, y + PADDING, width, height);
+
+        x += padded(width);
+        used_width = used_width.max(x);
+        shelf_height = shelf_height.max(padded(height));
+    }
+
+    (Quad::from_size(used_width, y + shelf_height), regions)
+}
+
+/// Copies an image into its region of the atlas, and extrudes its
+/// edge pixels into the padding around it.
+pub(crate) fn place_image(atlas: &mut image::RgbaImage, image: &image::RgbaImage, region: Quad) {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let padding = PADDING as i64;
+    for y in -padding..height as i64 + padding {
+        for x in -padding..width as i64 + padding {
+            let (atlas_x, atlas_y) = (region.min_x as i64 + x, region.min_y as i64 + y);
+            if atlas_x < 0
+                || atlas_y < 0
+                || atlas_x >= atlas.width() as i64
+                || atlas_y >= atlas.height() as i64
+            {
+                continue;
+            }
+
+            let source_x = x.clamp(0, width as i64 - 1) as u32;
+            let source_y = y.clamp(0, height as i64 - 1) as u32;
+            atlas.put_pixel(
+                atlas_x as u32,
+                atlas_y as u32,
+                *image.get_pixel(source_x, source_y),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_without_overlaps() {
+        let sizes = [(30, 10), (10, 40), (20, 20), (16, 16), (8, 8), (40, 5)];
+        let (atlas, regions) = pack_shelves(&sizes);
+
+        for (index, region) in regions.iter().enumerate() {
+            assert_eq!((region.width(), region.height()), sizes[index]);
+            assert!(region.max_x <= atlas.max_x && region.max_y <= atlas.max_y);
+
+            for other in &regions[index + 1..] {
+                assert_eq!(region.intersect(*other), None, "{:?} {:?}", region, other);
+            }
+        }
+    }
+
+    #[test]
+    fn packs_nothing() {
+        let (atlas, regions) = pack_shelves(&[]);
+
+        assert_eq!(atlas, Quad::from_size(0, 0));
+        assert!(regions.is_empty());
+    }
+
+    #[test]
+    fn packs_sizes_whose_area_overflows_a_u32() {
+        let sizes = [(70_000, 70_000), (70_000, 70_000)];
+        let (atlas, regions) = pack_shelves(&sizes);
+
+        assert!(atlas.width() >= 70_002);
+        assert_eq!(regions[0].intersect(regions[1]), None);
+    }
+
+    #[test]
+    fn edges_are_extruded_into_the_padding() {
+        let red = image::Rgba([255, 0, 0, 255]);
+        let blue = image::Rgba([0, 0, 255, 255]);
+        let image = image::RgbaImage::from_fn(2, 1, |x, _| if x == 0 { red } else { blue });
+
+        let (size, regions) = pack_shelves(&[(2, 1)]);
+        let mut atlas = image::RgbaImage::new(size.width(), size.height());
+        place_image(&mut atlas, &image, regions[0]);
+
+        assert_eq!(atlas.dimensions(), (4, 3));
+        for y in 0..3 {
+            assert_eq!(*atlas.get_pixel(0, y), red);
+            assert_eq!(*atlas.get_pixel(1, y), red);
+            assert_eq!(*atlas.get_pixel(2, y), blue);
+            assert_eq!(*atlas.get_pixel(3, y), blue);
+        }
+    }
+}
@@ -1,5 +1,13 @@
-use crate::math::Quad;
-use std::mem::size_of;
+use crate::{math::Quad, FragmentColor};
+use std::{
+    future::Future,
+    mem::size_of,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+type Error = Box<dyn std::error::Error>;
 
 // Based off wgpu example 'capture'
 #[derive(Debug, Clone)]
@@ -12,7 +20,16 @@ pub struct BufferSize {
 
 impl BufferSize {
     pub fn new(width: usize, height: usize) -> Self {
-        let bytes_per_pixel = size_of::<u32>();
+        Self::with_bytes_per_pixel(width, height, size_of::<u32>())
+    }
+
+    /// Buffer size for a texture format, i.e. 8 bytes per pixel for `Rgba16Float`
+    pub fn from_format(width: usize, height: usize, format: wgpu::TextureFormat) -> Self {
+        let bytes_per_pixel = format.block_size(None).unwrap_or(4) as usize;
+        Self::with_bytes_per_pixel(width, height, bytes_per_pixel)
+    }
+
+    pub fn with_bytes_per_pixel(width: usize, height: usize, bytes_per_pixel: usize) -> Self {
         let unpadded_bytes_per_row = width * bytes_per_pixel;
         let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize;
         let padded_bytes_per_row_padding = (align - unpadded_bytes_per_row % align) % align;
@@ -29,6 +46,17 @@ impl BufferSize {
     pub fn size(&self) -> u64 {
         self.padded_bytes_per_row as u64 * self.height as u64
     }
+
+    /// Strips the row padding required by wgpu for buffer copies,
+    /// returning tightly-packed rows of `unpadded_bytes_per_row`.
+    pub fn unpad(&self, padded: &[u8]) -> Vec<u8> {
+        padded
+            .chunks(self.padded_bytes_per_row as usize)
+            .take(self.height)
+            .flat_map(|row| &row[..self.unpadded_bytes_per_row])
+            .copied()
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -42,3 +70,140 @@ pub struct TextureBuffer {
     pub inner: Buffer,
     pub clip_region: Quad,
 }
+
+/// A staging buffer that receives a copy of a texture region.
+///
+/// Each read back gets its own staging buffer, so concurrent reads of
+/// the same target don't fight over one mapping, and no lock has to
+/// be held while waiting for the GPU.
+#[derive(Debug)]
+pub(crate) struct Readback {
+    buffer: wgpu::Buffer,
+    size: BufferSize,
+}
+
+impl Readback {
+    pub(crate) fn new(device: &wgpu::Device, size: BufferSize) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback buffer"),
+            size: size.size(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self { buffer, size }
+    }
+
+    pub(crate) fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    pub(crate) fn size(&self) -> &BufferSize {
+        &self.size
+    }
+
+    /// Maps the staging buffer and returns its unpadded contents.
+    ///
+    /// Must be called after the copy commands are submitted. The future
+    /// doesn't borrow the Renderer: it briefly locks it on each poll to
+    /// poll the device, so it can be awaited without holding any guard.
+    ///
+    /// With `Maintain::Poll`, the mapping is awaited without blocking on
+    /// the GPU. Blocking callers pass `Maintain::Wait` to finish the GPU
+    /// work first.
+    pub(crate) async fn read(self, maintain: wgpu::Maintain) -> Result<Vec<u8>, Error> {
+        let buffer_slice = self.buffer.slice(..);
+        let state = Arc::new(Mutex::new(MapState::default()));
+
+        let callback_state = state.clone();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            let mut state = callback_state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        _ = FragmentColor::poll(maintain);
+
+        let future = MapReadFuture { state };
+        if future.await.is_err() {
+            return Err("Failed to map texture buffer".into());
+        }
+
+        // We need to scope the mapped range so that we can unmap the buffer
+        let data = self.size.unpad(&buffer_slice.get_mapped_range());
+        self.buffer.unmap();
+
+        Ok(data)
+    }
+}
+
+#[derive(Default)]
+struct MapState {
+    result: Option<Result<(), wgpu::BufferAsyncError>>,
+    waker: Option<Waker>,
+}
+
+/// Resolves once a `map_async` callback has fired.
+///
+/// On native, nothing polls the device in the background, so each poll
+/// of this future nudges it with a non-blocking `Maintain::Poll` and
+/// wakes itself right away. Executors that only poll when woken, like
+/// `pollster`, keep polling until the mapping is ready instead of
+/// parking forever. On the Web, the callback is driven by the browser.
+struct MapReadFuture {
+    state: Arc<Mutex<MapState>>,
+}
+
+impl Future for MapReadFuture {
+    type Output = Result<(), wgpu::BufferAsyncError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        _ = FragmentColor::poll(wgpu::Maintain::Poll);
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(result) = state.result.take() {
+            return Poll::Ready(result);
+        }
+        state.waker = Some(cx.waker().clone());
+
+        #[cfg(not(wasm))]
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BufferSize;
+
+    #[test]
+    fn unpad_strips_row_alignment() {
+        let size = BufferSize::new(3, 2);
+        assert_eq!(size.unpadded_bytes_per_row, 12);
+        assert_eq!(size.padded_bytes_per_row, 256);
+
+        let mut padded = vec![0u8; size.size() as usize];
+        padded[..12].copy_from_slice(&[1; 12]);
+        padded[256..268].copy_from_slice(&[2; 12]);
+
+        let unpadded = size.unpad(&padded);
+
+        assert_eq!(unpadded.len(), 24);
+        assert!(unpadded[..12].iter().all(|byte| *byte == 1));
+        assert!(unpadded[12..].iter().all(|byte| *byte == 2));
+    }
+
+    #[test]
+    fn buffer_size_follows_texture_format() {
+        let size = BufferSize::from_format(3, 2, wgpu::TextureFormat::Rgba16Float);
+        assert_eq!(size.unpadded_bytes_per_row, 24);
+        assert_eq!(size.padded_bytes_per_row, 256);
+
+        let size = BufferSize::from_format(64, 1, wgpu::TextureFormat::Rgba32Float);
+        assert_eq!(size.unpadded_bytes_per_row, 1024);
+        assert_eq!(size.padded_bytes_per_row, 1024);
+    }
+}
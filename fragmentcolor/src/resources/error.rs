@@ -0,0 +1,31 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Errors that stop a texture from being loaded.
+#[derive(Debug)]
+pub enum TextureError {
+    /// The container stores a pixel format that is not block-compressed,
+    /// or one that FragmentColor can't upload as is.
+    UnknownCompressedFormat(String),
+
+    /// The GPU doesn't support this block-compressed format.
+    ///
+    /// BC formats are common on desktop, ETC2 and ASTC on mobile.
+    UnsupportedCompressedFormat(wgpu::TextureFormat),
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCompressedFormat(format) => {
+                write!(f, "Unknown compressed texture format: {}", format)
+            }
+            Self::UnsupportedCompressedFormat(format) => write!(
+                f,
+                "Compressed texture format {:?} is not supported by this GPU",
+                format
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {}
@@ -97,6 +97,7 @@ pub struct MeshBuilder {
     vertices: Vec<VertexData>,
     type_infos: Vec<hecs::TypeInfo>,
     vertex_count: usize,
+    max_index: Option<u32>,
     bound_radius: f32,
 }
 
@@ -109,6 +110,7 @@ impl MeshBuilder {
             vertices: Vec::new(),
             type_infos: Vec::new(),
             vertex_count: 0,
+            max_index: None,
             bound_radius: 0.0,
         }
     }
@@ -119,20 +121,44 @@ impl MeshBuilder {
     }
 
     fn append<T: bytemuck::Pod>(&mut self, data: &[T]) -> wgpu::BufferAddress {
+        // Vertex and index buffer offsets must be 4-byte aligned,
+        // which an odd number of u16 indices would break.
+        let alignment = wgpu::VERTEX_STRIDE_ALIGNMENT as usize;
+        let padding = (alignment - self.data.len() % alignment) % alignment;
+        self.data.extend(std::iter::repeat(0).take(padding));
+
         let offset = self.data.len();
         self.data.extend(bytemuck::cast_slice(data));
         offset as _
     }
 
+    /// Sets 16-bit indices. Prefer this for meshes under 65536 vertices.
     pub fn index(&mut self, data: &[u16]) -> &mut Self {
+        let max_index = data.iter().max().map(|index| *index as u32);
+        self.set_indices(data, wgpu::IndexFormat::Uint16, max_index)
+    }
+
+    /// Sets 32-bit indices, for meshes with more than 65536 vertices.
+    pub fn index_u32(&mut self, data: &[u32]) -> &mut Self {
+        let max_index = data.iter().max().copied();
+        self.set_indices(data, wgpu::IndexFormat::Uint32, max_index)
+    }
+
+    fn set_indices<T: bytemuck::Pod>(
+        &mut self,
+        data: &[T],
+        format: wgpu::IndexFormat,
+        max_index: Option<u32>,
+    ) -> &mut Self {
         // @FIXME ALL asserts and panics must go away and return a Result
         assert!(self.vertex_ids.is_none());
         let offset = self.append(data);
         self.vertex_ids = Some(VertexIds {
             offset,
-            format: wgpu::IndexFormat::Uint16,
+            format,
             count: data.len() as u32,
         });
+        self.max_index = max_index;
         self
     }
 
@@ -159,6 +185,16 @@ impl MeshBuilder {
     }
 
     pub fn build(&mut self) -> Result<BuiltMesh, Error> {
+        if let Some(max_index) = self.max_index {
+            if max_index as usize >= self.vertex_count {
+                return Err(format!(
+                    "Mesh index {} is out of range for {} vertices",
+                    max_index, self.vertex_count
+                )
+                .into());
+            }
+        }
+
         let renderer = FragmentColor::renderer();
         let renderer = if let Ok(renderer) = renderer.try_read() {
             renderer
@@ -188,6 +224,7 @@ impl MeshBuilder {
             .collect::<Vec<_>>()
             .into_boxed_slice();
 
+        self.max_index = None;
         let mesh_id = renderer.add_mesh(MeshData {
             buffer,
             vertex_ids: self.vertex_ids.take(),
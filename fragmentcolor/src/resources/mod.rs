@@ -1,12 +1,18 @@
+pub(crate) mod atlas;
 pub(crate) mod buffer;
+mod error;
 pub(crate) mod loaders;
 pub mod mesh;
 pub mod resources;
 pub(crate) mod sampler;
 pub(crate) mod shaders;
 pub mod texture;
+pub mod texture_stream;
 
+pub use error::*;
 pub use resources::*;
+pub use sampler::SamplerOptions;
 pub use texture::*;
+pub use texture_stream::*;
 
 pub use mesh::*;
@@ -30,6 +30,10 @@ impl Resources {
         self.textures.get(id)
     }
 
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+
     pub fn add_mesh(&mut self, mesh: MeshData) -> MeshId {
         let index = MeshId(MESH_ID.fetch_add(1, Ordering::Relaxed));
         self.meshes.insert(index, mesh);
@@ -1,53 +1,86 @@
+/// Addressing and filtering options for a texture sampler
 #[derive(Debug, Clone)]
-pub(crate) struct SamplerOptions {
-    pub repeat_x: bool,
-    pub repeat_y: bool,
-    pub smooth: bool,
+pub struct SamplerOptions {
+    pub address_mode_u: wgpu::AddressMode,
+    pub address_mode_v: wgpu::AddressMode,
+    pub address_mode_w: wgpu::AddressMode,
+    pub mag_filter: wgpu::FilterMode,
+    pub min_filter: wgpu::FilterMode,
+    pub mipmap_filter: wgpu::FilterMode,
+    /// Values above 1 require all filters to be `Linear`.
+    pub anisotropy_clamp: u16,
     pub compare: Option<wgpu::CompareFunction>,
 }
 
 impl Default for SamplerOptions {
     fn default() -> Self {
         Self {
-            repeat_x: false,
-            repeat_y: false,
-            smooth: true,
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            anisotropy_clamp: 1,
             compare: None,
         }
     }
 }
 
+impl SamplerOptions {
+    /// Tiling sampler: repeats the texture on all axes
+    pub fn repeat() -> Self {
+        Self {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            ..Default::default()
+        }
+    }
+
+    /// Pixelated sampler: no filtering between texels or mips
+    pub fn nearest() -> Self {
+        Self {
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        }
+    }
+}
+
 pub(crate) fn create_default_sampler(device: &wgpu::Device) -> wgpu::Sampler {
     create_sampler(device, SamplerOptions::default())
 }
 
 pub(crate) fn create_sampler(device: &wgpu::Device, options: SamplerOptions) -> wgpu::Sampler {
     let label = format!("{:?}", options);
-    let address_mode_u = match options.repeat_x {
-        true => wgpu::AddressMode::Repeat,
-        false => wgpu::AddressMode::ClampToEdge,
-    };
-    let address_mode_v = match options.repeat_y {
-        true => wgpu::AddressMode::Repeat,
-        false => wgpu::AddressMode::ClampToEdge,
-    };
-    let filter = match options.smooth {
-        true => wgpu::FilterMode::Linear,
-        false => wgpu::FilterMode::Nearest,
+
+    // Anisotropic filtering is only valid when every filter is linear
+    let linear = options.mag_filter == wgpu::FilterMode::Linear
+        && options.min_filter == wgpu::FilterMode::Linear
+        && options.mipmap_filter == wgpu::FilterMode::Linear;
+    let anisotropy_clamp = if linear {
+        options.anisotropy_clamp.clamp(1, 16)
+    } else {
+        if options.anisotropy_clamp > 1 {
+            log::warn!("Anisotropic filtering requires linear filters. Ignoring anisotropy_clamp.");
+        }
+        1
     };
 
     device.create_sampler(&wgpu::SamplerDescriptor {
         label: Some(&label),
-        address_mode_u,
-        address_mode_v,
-        address_mode_w: address_mode_v,
-        mag_filter: filter,
-        min_filter: filter,
-        mipmap_filter: filter,
+        address_mode_u: options.address_mode_u,
+        address_mode_v: options.address_mode_v,
+        address_mode_w: options.address_mode_w,
+        mag_filter: options.mag_filter,
+        min_filter: options.min_filter,
+        mipmap_filter: options.mipmap_filter,
         lod_min_clamp: 0.0,
         lod_max_clamp: 100.0,
         compare: options.compare,
-        anisotropy_clamp: 1,
+        anisotropy_clamp,
         border_color: None,
     })
 }
@@ -1,18 +1,29 @@
 use crate::{
     app,
-    renderer::{target::Dimensions, Renderer},
-    resources::sampler::{create_default_sampler, create_sampler, SamplerOptions},
+    renderer::{target::Dimensions, RenderContext, Renderer, TargetId, ToneMapOptions},
+    resources::{
+        atlas,
+        error::TextureError,
+        sampler::{create_default_sampler, create_sampler, SamplerOptions},
+        texture_stream::{TextureInput, TextureStream},
+    },
     FragmentColor, Quad,
 };
 use image::{DynamicImage, GenericImageView};
 use std::path::Path;
+use wgpu::util::DeviceExt;
 
 type Error = Box<dyn std::error::Error>;
 
 const DEFAULT_IMAGE: &str = "default.jpg";
+const DDS_MAGIC: &[u8] = b"DDS ";
+const KTX2_MAGIC: &[u8] = b"\xABKTX 20\xBB\r\n\x1A\n";
 pub(crate) const DEFAULT_IMAGE_SIZE: (u32, u32) = (1200, 1200);
 
 /// Represents a loaded texture in the GPU
+///
+/// Texture targets are identified by the TextureId of their texture,
+/// so its accessors work for them too.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TextureId(wgpu::Id<wgpu::Texture>);
 
@@ -28,6 +39,70 @@ impl Default for TextureId {
     }
 }
 
+impl TextureId {
+    /// The format of this texture, or None if it doesn't exist.
+    pub fn format(&self) -> Option<wgpu::TextureFormat> {
+        self.inspect(|texture| texture.format())
+    }
+
+    /// The size of this texture, or None if it doesn't exist.
+    pub fn size(&self) -> Option<Quad> {
+        self.inspect(|texture| texture.size())
+    }
+
+    /// How this texture can be used, or None if it doesn't exist.
+    pub fn usage(&self) -> Option<wgpu::TextureUsages> {
+        self.inspect(|texture| texture.usage())
+    }
+
+    /// Whether this texture exists and allows all the given usages.
+    ///
+    /// For example, a texture needs `RENDER_ATTACHMENT` to be a Render Target.
+    pub fn has_usage(&self, usage: wgpu::TextureUsages) -> bool {
+        self.inspect(|texture| texture.has_usage(usage))
+            .unwrap_or(false)
+    }
+
+    fn inspect<T>(&self, read: impl FnOnce(&Texture) -> T) -> Option<T> {
+        let renderer = FragmentColor::renderer();
+        let renderer = renderer.try_read().ok()?;
+
+        renderer.inspect_texture(self, read)
+    }
+}
+
+/// Options for textures created from images
+#[derive(Debug, Clone)]
+pub struct TextureOptions {
+    /// Generates a full mip chain at upload time.
+    ///
+    /// Reduces aliasing when a large texture is drawn at a small size.
+    /// The levels are downsampled on the CPU in linear color space, so
+    /// uploads take longer and use about four times the image's memory
+    /// while they are built. Prefer DDS files with baked mips for big
+    /// texture sets.
+    pub generate_mipmaps: bool,
+
+    /// Sampler used when this texture is bound to a shader
+    pub sampler: SamplerOptions,
+
+    /// Whether the image holds sRGB-encoded colors (the default).
+    ///
+    /// Set it to false for data textures such as normal maps,
+    /// so the GPU samples the raw values without gamma decoding.
+    pub srgb: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            generate_mipmaps: false,
+            sampler: SamplerOptions::default(),
+            srgb: true,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Texture {
     pub id: TextureId,
@@ -53,6 +128,96 @@ impl Dimensions for Texture {
 }
 
 impl Texture {
+    /// The format of this texture
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    /// How this texture can be used
+    pub fn usage(&self) -> wgpu::TextureUsages {
+        self.data.usage()
+    }
+
+    /// Whether this texture allows all the given usages
+    pub fn has_usage(&self, usage: wgpu::TextureUsages) -> bool {
+        self.usage().contains(usage)
+    }
+
+    /// Copies a region of one texture into another on the GPU.
+    ///
+    /// Useful to snapshot a Texture target into a loaded texture without
+    /// reading it back to the CPU, or to stamp a loaded texture into a
+    /// target. The textures must be different and have the same format,
+    /// and the region (the whole source by default) must fit in both.
+    pub fn copy(
+        source: &TextureId,
+        destination: &TextureId,
+        region: Option<Quad>,
+    ) -> Result<(), Error> {
+        let renderer = FragmentColor::renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Renderer is locked. Cannot copy Texture!".into());
+        };
+
+        renderer.copy_texture(source, destination, region)
+    }
+
+    /// Draws a texture stretched over a whole render target.
+    ///
+    /// Unlike `Texture::copy`, the formats and sizes can differ: the source
+    /// is sampled with linear filtering and converted to the target format.
+    /// Use it to show a texture on a Window or to convert between formats.
+    pub fn blit(source: &TextureId, target: &TargetId) -> Result<(), Error> {
+        let renderer = FragmentColor::renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Renderer is locked. Cannot blit Texture!".into());
+        };
+
+        renderer.blit(source, target)
+    }
+
+    /// Draws an HDR texture over a whole render target with tone mapping.
+    ///
+    /// Reads a high dynamic range texture, like a Rgba16Float target,
+    /// and writes colors the target can display. The exposure scales
+    /// the colors before the operator compresses them into [0, 1].
+    pub fn tonemap(
+        source: &TextureId,
+        target: &TargetId,
+        options: ToneMapOptions,
+    ) -> Result<(), Error> {
+        let renderer = FragmentColor::renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Renderer is locked. Cannot tone map Texture!".into());
+        };
+
+        renderer.tonemap(source, target, options)
+    }
+
+    /// Removes a texture from the Renderer, releasing its GPU memory.
+    ///
+    /// The memory is freed once the GPU finishes any work already
+    /// submitted with it. Sprites still using this texture are a logic
+    /// error: they will draw the placeholder texture with a warning.
+    ///
+    /// Returns false if the texture was not loaded.
+    pub fn free(id: &TextureId) -> Result<bool, Error> {
+        let renderer = FragmentColor::renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Renderer is locked. Cannot free Texture!".into());
+        };
+
+        Ok(renderer.remove_texture(id)?.is_some())
+    }
+
     /// Loads a default "Image Not Found" texture
     pub(crate) fn image_not_found() -> Result<(TextureId, Quad), Error> {
         let default = Path::new(&app::ASSETS).join(DEFAULT_IMAGE);
@@ -64,25 +229,291 @@ impl Texture {
     ///
     /// Returns the Texture Id and the Quad with the size of the loaded texture
     pub fn from_file(path: impl AsRef<Path>) -> Result<(TextureId, Quad), Error> {
+        Self::from_file_with(path, TextureOptions::default())
+    }
+
+    /// Creates a texture from a file with custom options
+    pub fn from_file_with(
+        path: impl AsRef<Path>,
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
         let image = image::open(path)?;
         let size = image.dimensions();
-        let texture_id = Self::from_loaded_image(&image)?;
+        let texture_id = Self::from_loaded_image(&image, &options)?;
 
         Ok((texture_id, Quad::from_tuple(size)))
     }
 
+    /// Creates textures from many files, decoding them in parallel.
+    ///
+    /// Images are decoded on one thread per CPU core and uploaded to
+    /// the GPU as soon as each one is ready. `on_progress` is called
+    /// after every upload with the number of finished textures and
+    /// the total, so apps can show a loading bar.
+    ///
+    /// Returns the results in the same order as `paths`. This blocks
+    /// until all textures are loaded. Use `Texture::stream()` to await
+    /// them one by one instead.
+    #[cfg(not(wasm))]
+    pub fn from_files<P: AsRef<Path>>(
+        paths: &[P],
+        options: TextureOptions,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Vec<Result<(TextureId, Quad), Error>> {
+        let inputs = paths
+            .iter()
+            .map(|path| TextureInput::from(path.as_ref()))
+            .collect();
+        let mut stream = Self::stream(inputs, options);
+
+        let mut results: Vec<Option<Result<(TextureId, Quad), Error>>> =
+            std::iter::repeat_with(|| None)
+                .take(stream.total())
+                .collect();
+
+        while let Some((index, result)) = pollster::block_on(stream.next()) {
+            results[index] = Some(result);
+            on_progress(stream.loaded(), stream.total());
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err("Texture was not loaded".into())))
+            .collect()
+    }
+
+    /// Loads many textures, yielding each one as soon as it's uploaded.
+    ///
+    /// Await `next()` on the returned stream until it returns None. Each
+    /// texture comes with the position of its input, in the order they
+    /// finish. See `TextureStream` for how each platform decodes them.
+    pub fn stream(inputs: Vec<TextureInput>, options: TextureOptions) -> TextureStream {
+        TextureStream::new(inputs, options)
+    }
+
     /// Creates a new texture resource from raw bytes array
     ///
     /// Makes an educated guess about the image format
     /// and automatically detects Width and Height.
     pub fn from_bytes(bytes: &[u8]) -> Result<(TextureId, Quad), Error> {
+        Self::from_bytes_with(bytes, TextureOptions::default())
+    }
+
+    /// Creates a new texture resource from raw bytes array with custom options
+    pub fn from_bytes_with(
+        bytes: &[u8],
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
+        // Block-compressed DDS files are uploaded as they are when the GPU
+        // supports their format. Otherwise the image crate decompresses them.
+        if bytes.starts_with(DDS_MAGIC) {
+            match Self::from_dds_bytes_with(bytes, options.clone()) {
+                Ok(texture) => return Ok(texture),
+                Err(error) => log::warn!("{}. Decompressing it on the CPU instead.", error),
+            }
+        }
+
+        // The image crate can't read KTX2 files, so there's no fallback
+        if bytes.starts_with(KTX2_MAGIC) {
+            return Self::from_ktx2_bytes_with(bytes, options);
+        }
+
         let image = image::load_from_memory(bytes)?;
         let size = image.dimensions();
-        let texture_id = Self::from_loaded_image(&image)?;
+        let texture_id = Self::from_loaded_image(&image, &options)?;
 
         Ok((texture_id, Quad::from_tuple(size)))
     }
 
+    /// Creates a texture from a block-compressed DDS file in memory
+    ///
+    /// The compressed data and its mip chain are uploaded directly,
+    /// without decompression. Supports BC1 to BC7 (DXT1, DXT3, DXT5).
+    ///
+    /// Returns a `TextureError` if the format is not block-compressed
+    /// or if the GPU does not support it.
+    pub fn from_dds_bytes(bytes: &[u8]) -> Result<(TextureId, Quad), Error> {
+        Self::from_dds_bytes_with(bytes, TextureOptions::default())
+    }
+
+    /// Creates a texture from a block-compressed DDS file with custom options
+    ///
+    /// `options.srgb` picks the sRGB or linear variant of the format, even
+    /// if the DDS header says otherwise. The mip chain always comes from
+    /// the file, so `options.generate_mipmaps` is ignored.
+    pub fn from_dds_bytes_with(
+        bytes: &[u8],
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
+        let dds = ddsfile::Dds::read(bytes)?;
+        let format = if let Some(format) = dds_format(&dds) {
+            format
+        } else {
+            let format = dds
+                .get_dxgi_format()
+                .map(|format| format!("{:?}", format))
+                .or_else(|| dds.get_d3d_format().map(|format| format!("{:?}", format)))
+                .unwrap_or_else(|| "unknown DDS format".to_string());
+            return Err(TextureError::UnknownCompressedFormat(format).into());
+        };
+
+        Self::from_compressed_data(
+            format,
+            dds.get_width(),
+            dds.get_height(),
+            dds.get_num_mipmap_levels().max(1),
+            &dds.data,
+            options,
+        )
+    }
+
+    /// Creates a texture from a block-compressed KTX2 file in memory
+    ///
+    /// The compressed data and its mip chain are uploaded directly,
+    /// without decompression. Supports BC1 to BC7, ETC2, EAC and ASTC.
+    /// Supercompressed files (Basis Universal, Zstandard) are not supported.
+    ///
+    /// Returns a `TextureError` if the format is not block-compressed
+    /// or if the GPU does not support it.
+    pub fn from_ktx2_bytes(bytes: &[u8]) -> Result<(TextureId, Quad), Error> {
+        Self::from_ktx2_bytes_with(bytes, TextureOptions::default())
+    }
+
+    /// Creates a texture from a block-compressed KTX2 file with custom options
+    ///
+    /// `options.srgb` picks the sRGB or linear variant of the format, even
+    /// if the KTX2 header says otherwise. The mip chain always comes from
+    /// the file, so `options.generate_mipmaps` is ignored.
+    pub fn from_ktx2_bytes_with(
+        bytes: &[u8],
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
+        let ktx2 = read_ktx2(bytes)?;
+
+        Self::from_compressed_data(
+            ktx2.format,
+            ktx2.width,
+            ktx2.height,
+            ktx2.mip_level_count,
+            &ktx2.data,
+            options,
+        )
+    }
+
+    /// Uploads block-compressed data and its mip chain as they are
+    fn from_compressed_data(
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        mip_level_count: u32,
+        data: &[u8],
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
+        let format = if options.srgb {
+            format.add_srgb_suffix()
+        } else {
+            format.remove_srgb_suffix()
+        };
+
+        let renderer = FragmentColor::renderer();
+        let renderer = if let Ok(renderer) = renderer.try_read() {
+            renderer
+        } else {
+            return Err("Cannot read Renderer Texture Database. Texture not loaded!".into());
+        };
+
+        let required_features = format.required_features();
+        if !renderer.device.features().contains(required_features) {
+            return Err(TextureError::UnsupportedCompressedFormat(format).into());
+        }
+
+        let (block_width, block_height) = format.block_dimensions();
+        if width % block_width != 0 || height % block_height != 0 {
+            return Err(format!(
+                "Compressed texture size must be a multiple of {}x{} (got {}x{}).",
+                block_width, block_height, width, height
+            )
+            .into());
+        }
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let mut descriptor = Self::source_texture_descriptor("Compressed texture", size, format);
+        descriptor.mip_level_count = mip_level_count;
+
+        let texture = renderer
+            .device
+            .create_texture_with_data(&renderer.queue, &descriptor, data);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = create_sampler(&renderer.device, options.sampler);
+
+        let texture = Self {
+            id: TextureId(texture.global_id()),
+            data: texture,
+            size,
+            view,
+            format,
+            sampler,
+        };
+
+        Ok((
+            renderer.add_texture(texture)?,
+            Quad::from_size(width, height),
+        ))
+    }
+
+    /// Creates a texture from an image already loaded in memory
+    pub fn from_image(
+        image: &DynamicImage,
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad), Error> {
+        let texture_id = Self::from_loaded_image(image, &options)?;
+
+        Ok((texture_id, Quad::from_tuple(image.dimensions())))
+    }
+
+    /// Packs many images into a single texture atlas.
+    ///
+    /// Drawing Sprites from one atlas avoids switching textures
+    /// between them. Returns the atlas, its size and the region of
+    /// each image in pixels, in the same order as `images`. Use the
+    /// regions as the `clip_region` of each Sprite.
+    pub fn create_atlas(
+        images: &[DynamicImage],
+        options: TextureOptions,
+    ) -> Result<(TextureId, Quad, Vec<Quad>), Error> {
+        let sizes: Vec<(u32, u32)> = images.iter().map(|image| image.dimensions()).collect();
+        let (size, regions) = atlas::pack_shelves(&sizes);
+
+        let max_size = FragmentColor::limits().max_texture_dimension_2d;
+        if size.width() > max_size || size.height() > max_size {
+            return Err(format!(
+                "Atlas size {}x{} exceeds the maximum texture size {}",
+                size.width(),
+                size.height(),
+                max_size
+            )
+            .into());
+        }
+
+        let mut atlas = image::RgbaImage::new(size.width().max(1), size.height().max(1));
+        for (image, region) in images.iter().zip(&regions) {
+            atlas::place_image(&mut atlas, &image.to_rgba8(), *region);
+        }
+
+        let (texture_id, _) = Self::from_image(&DynamicImage::ImageRgba8(atlas), options)?;
+
+        Ok((texture_id, size, regions))
+    }
+
+    /// Number of mip levels in a full chain for the given size
+    pub fn mip_level_count(width: u32, height: u32) -> u32 {
+        32 - width.max(height).max(1).leading_zeros()
+    }
+
     /// Internal method to create a Texture marked as a destination for rendering
     ///
     /// Unlike the other methods that create a Texture resource in the GPU and
@@ -90,6 +521,19 @@ impl Texture {
     ///
     /// This method is used internally by the `Target::create_texture()` method.
     pub(crate) fn create_destination_texture(size: wgpu::Extent3d) -> Result<Self, Error> {
+        Self::create_destination_texture_with_format(size, wgpu::TextureFormat::Rgba8UnormSrgb)
+    }
+
+    /// Same as `create_destination_texture()`, with a custom color format.
+    ///
+    /// The format must be renderable on the current adapter. Render passes
+    /// alpha blend into targets and sample them with linear filtering, so
+    /// the format must also be blendable and filterable. Use it to create
+    /// float targets (i.e. `Rgba16Float`) for HDR pipelines.
+    pub(crate) fn create_destination_texture_with_format(
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, Error> {
         let renderer = FragmentColor::renderer();
         let renderer = if let Ok(renderer) = renderer.try_read() {
             renderer
@@ -98,8 +542,26 @@ impl Texture {
             return Err("Renderer is locked. Cannot build texture!".into());
         };
 
+        let features = renderer.adapter.get_texture_format_features(format);
+        if !features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+            || format.is_depth_stencil_format()
+        {
+            return Err(format!("Texture format {:?} is not color-renderable", format).into());
+        }
+
+        let required = wgpu::TextureFormatFeatureFlags::BLENDABLE
+            | wgpu::TextureFormatFeatureFlags::FILTERABLE;
+        if !features.flags.contains(required) {
+            return Err(format!(
+                "Texture format {:?} must be blendable and filterable to be a Render Target",
+                format
+            )
+            .into());
+        }
+
         let label = "Render Target Texture";
-        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
         let descriptor = Self::target_texture_descriptor(label, size, format);
         let texture = renderer.device.create_texture(&descriptor);
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -137,10 +599,8 @@ impl Texture {
         let sampler = create_sampler(
             &renderer.device,
             SamplerOptions {
-                repeat_x: false,
-                repeat_y: false,
-                smooth: true,
                 compare: Some(wgpu::CompareFunction::LessEqual),
+                ..Default::default()
             },
         );
 
@@ -205,7 +665,10 @@ impl Texture {
     /// Internal method to create a TextureId from a DynamicImage instance.
     ///
     /// The image is already loaded in memory at this point.
-    fn from_loaded_image(image: &DynamicImage) -> Result<TextureId, Error> {
+    pub(crate) fn from_loaded_image(
+        image: &DynamicImage,
+        options: &TextureOptions,
+    ) -> Result<TextureId, Error> {
         let label = "Source texture";
         let (width, height) = image.dimensions();
         let size = wgpu::Extent3d {
@@ -213,8 +676,18 @@ impl Texture {
             height,
             depth_or_array_layers: 1,
         };
-        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
-        let descriptor = Self::source_texture_descriptor(label, size, format);
+        let format = if options.srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        let mip_level_count = if options.generate_mipmaps {
+            Self::mip_level_count(width, height)
+        } else {
+            1
+        };
+        let mut descriptor = Self::source_texture_descriptor(label, size, format);
+        descriptor.mip_level_count = mip_level_count;
 
         let renderer = FragmentColor::renderer();
         let renderer = if let Ok(renderer) = renderer.try_read() {
@@ -226,10 +699,16 @@ impl Texture {
         let texture = renderer.device.create_texture(&descriptor);
 
         let source = image.to_rgba8();
-        Self::write_data_to_texture(&renderer, source, &texture, size);
+        Self::write_data_to_texture(&renderer, &source, &texture, 0, size);
+
+        let mips = mip_chain(&source, options.srgb, mip_level_count);
+        for (mip_level, mip) in (1..).zip(&mips) {
+            let mip_size = size.mip_level_size(mip_level, wgpu::TextureDimension::D2);
+            Self::write_data_to_texture(&renderer, mip, &texture, mip_level, mip_size);
+        }
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = create_default_sampler(&renderer.device);
+        let sampler = create_sampler(&renderer.device, options.sampler.clone());
 
         let texture = Self {
             id: TextureId(texture.global_id()),
@@ -244,6 +723,9 @@ impl Texture {
     }
 
     /// Creates a texture descriptor for a Source Texture
+    ///
+    /// Loaded textures can be sampled, written and copied from,
+    /// so `Texture::copy` accepts them on either side.
     fn source_texture_descriptor(
         label: &str,
         size: wgpu::Extent3d,
@@ -256,7 +738,9 @@ impl Texture {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         }
     }
@@ -277,6 +761,7 @@ impl Texture {
             view_formats: &[],
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT
                 | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST
                 | wgpu::TextureUsages::TEXTURE_BINDING,
         }
     }
@@ -284,8 +769,9 @@ impl Texture {
     /// Writes pixel data to a texture
     fn write_data_to_texture(
         renderer: &Renderer,
-        origin_image: image::RgbaImage,
+        origin_image: &image::RgbaImage,
         target_texture: &wgpu::Texture,
+        mip_level: u32,
         size: wgpu::Extent3d,
     ) {
         renderer.queue.write_texture(
@@ -293,11 +779,11 @@ impl Texture {
             wgpu::ImageCopyTexture {
                 aspect: wgpu::TextureAspect::All,
                 texture: target_texture,
-                mip_level: 0,
+                mip_level,
                 origin: wgpu::Origin3d::ZERO,
             },
             // The actual pixel data
-            &origin_image,
+            origin_image,
             // The layout of the texture
             wgpu::ImageDataLayout {
                 offset: 0,
@@ -308,3 +794,488 @@ impl Texture {
         )
     }
 }
+
+/// Downsamples the mip levels after the first one from the full image.
+///
+/// Colors are averaged in linear space, so sRGB textures don't get darker.
+fn mip_chain(source: &image::RgbaImage, srgb: bool, mip_level_count: u32) -> Vec<image::RgbaImage> {
+    if mip_level_count <= 1 {
+        return Vec::new();
+    }
+
+    let size = wgpu::Extent3d {
+        width: source.width(),
+        height: source.height(),
+        depth_or_array_layers: 1,
+    };
+    let linear = linear_image(source, srgb);
+
+    (1..mip_level_count)
+        .map(|mip_level| {
+            let mip_size = size.mip_level_size(mip_level, wgpu::TextureDimension::D2);
+            let mip = image::imageops::resize(
+                &linear,
+                mip_size.width,
+                mip_size.height,
+                image::imageops::FilterType::Triangle,
+            );
+            encoded_image(&mip, srgb)
+        })
+        .collect()
+}
+
+/// Decodes an image to linear floats, so it can be filtered without
+/// shifting its colors. Alpha is always linear.
+fn linear_image(image: &image::RgbaImage, srgb: bool) -> image::Rgba32FImage {
+    let decode = |value: u8| {
+        let value = value as f32 / 255.0;
+        if !srgb {
+            value
+        } else if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    image::Rgba32FImage::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+        image::Rgba([decode(r), decode(g), decode(b), a as f32 / 255.0])
+    })
+}
+
+/// Encodes a linear image back to bytes. Reverses `linear_image()`.
+fn encoded_image(image: &image::Rgba32FImage, srgb: bool) -> image::RgbaImage {
+    let encode = |value: f32| {
+        let value = value.clamp(0.0, 1.0);
+        let value = if !srgb {
+            value
+        } else if value <= 0.0031308 {
+            value * 12.92
+        } else {
+            1.055 * value.powf(1.0 / 2.4) - 0.055
+        };
+        (value * 255.0).round() as u8
+    };
+
+    image::RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b, a] = image.get_pixel(x, y).0;
+        image::Rgba([
+            encode(r),
+            encode(g),
+            encode(b),
+            (a.clamp(0.0, 1.0) * 255.0).round() as u8,
+        ])
+    })
+}
+
+/// Whether the bytes are a DDS or KTX2 file, which may be uploaded without decoding
+pub(crate) fn is_compressed_container(bytes: &[u8]) -> bool {
+    bytes.starts_with(DDS_MAGIC) || bytes.starts_with(KTX2_MAGIC)
+}
+
+/// Maps the pixel format of a DDS file to a block-compressed wgpu format
+fn dds_format(dds: &ddsfile::Dds) -> Option<wgpu::TextureFormat> {
+    use ddsfile::{D3DFormat, DxgiFormat};
+    use wgpu::TextureFormat as Format;
+
+    if let Some(format) = dds.get_dxgi_format() {
+        return match format {
+            DxgiFormat::BC1_UNorm => Some(Format::Bc1RgbaUnorm),
+            DxgiFormat::BC1_UNorm_sRGB => Some(Format::Bc1RgbaUnormSrgb),
+            DxgiFormat::BC2_UNorm => Some(Format::Bc2RgbaUnorm),
+            DxgiFormat::BC2_UNorm_sRGB => Some(Format::Bc2RgbaUnormSrgb),
+            DxgiFormat::BC3_UNorm => Some(Format::Bc3RgbaUnorm),
+            DxgiFormat::BC3_UNorm_sRGB => Some(Format::Bc3RgbaUnormSrgb),
+            DxgiFormat::BC4_UNorm => Some(Format::Bc4RUnorm),
+            DxgiFormat::BC4_SNorm => Some(Format::Bc4RSnorm),
+            DxgiFormat::BC5_UNorm => Some(Format::Bc5RgUnorm),
+            DxgiFormat::BC5_SNorm => Some(Format::Bc5RgSnorm),
+            DxgiFormat::BC6H_UF16 => Some(Format::Bc6hRgbUfloat),
+            DxgiFormat::BC6H_SF16 => Some(Format::Bc6hRgbFloat),
+            DxgiFormat::BC7_UNorm => Some(Format::Bc7RgbaUnorm),
+            DxgiFormat::BC7_UNorm_sRGB => Some(Format::Bc7RgbaUnormSrgb),
+            _ => None,
+        };
+    }
+
+    // Legacy headers don't store the color space. The caller picks it.
+    match dds.get_d3d_format()? {
+        D3DFormat::DXT1 => Some(Format::Bc1RgbaUnormSrgb),
+        D3DFormat::DXT2 | D3DFormat::DXT3 => Some(Format::Bc2RgbaUnormSrgb),
+        D3DFormat::DXT4 | D3DFormat::DXT5 => Some(Format::Bc3RgbaUnormSrgb),
+        _ => None,
+    }
+}
+
+/// The parts of a KTX2 file needed to upload it
+struct Ktx2 {
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    mip_level_count: u32,
+    data: Vec<u8>, // mip levels in order, from the largest
+}
+
+/// Reads the header and mip levels of a KTX2 file
+///
+/// Only 2D textures with a single layer and face are supported.
+fn read_ktx2(bytes: &[u8]) -> Result<Ktx2, Error> {
+    const HEADER_SIZE: usize = 80;
+    const LEVEL_SIZE: usize = 24;
+
+    if !bytes.starts_with(KTX2_MAGIC) || bytes.len() < HEADER_SIZE {
+        return Err("Invalid KTX2 file header.".into());
+    }
+    let read_u32 =
+        |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let read_u64 =
+        |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+    let vk_format = read_u32(12);
+    let (width, height, depth) = (read_u32(20), read_u32(24), read_u32(28));
+    let (layers, faces, levels) = (read_u32(32), read_u32(36), read_u32(40));
+    let supercompression = read_u32(44);
+
+    let format = if let Some(format) = ktx2_format(vk_format) {
+        format
+    } else {
+        return Err(
+            TextureError::UnknownCompressedFormat(format!("VkFormat {}", vk_format)).into(),
+        );
+    };
+    if supercompression != 0 {
+        return Err("Supercompressed KTX2 files are not supported.".into());
+    }
+    if depth > 1 || layers > 1 || faces != 1 {
+        return Err("Only 2D KTX2 textures with a single layer are supported.".into());
+    }
+
+    // Zero levels asks the loader to generate them, which can't be done
+    // for compressed data. The file still stores the base level.
+    let mip_level_count = levels.max(1);
+    let index_end = HEADER_SIZE + mip_level_count as usize * LEVEL_SIZE;
+    if bytes.len() < index_end {
+        return Err("KTX2 file is truncated.".into());
+    }
+
+    let mut data = Vec::new();
+    for level in 0..mip_level_count as usize {
+        let entry = HEADER_SIZE + level * LEVEL_SIZE;
+        let (offset, length) = (read_u64(entry), read_u64(entry + 8));
+        let level_data = usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(length).ok())
+            .and_then(|(offset, length)| bytes.get(offset..offset.checked_add(length)?));
+
+        if let Some(level_data) = level_data {
+            data.extend_from_slice(level_data);
+        } else {
+            return Err("KTX2 file is truncated.".into());
+        }
+    }
+
+    Ok(Ktx2 {
+        format,
+        width,
+        height,
+        mip_level_count,
+        data,
+    })
+}
+
+/// Maps the VkFormat of a KTX2 file to a block-compressed wgpu format
+fn ktx2_format(vk_format: u32) -> Option<wgpu::TextureFormat> {
+    use wgpu::{AstcBlock, AstcChannel, TextureFormat as Format};
+
+    let format = match vk_format {
+        131 | 133 => Format::Bc1RgbaUnorm,
+        132 | 134 => Format::Bc1RgbaUnormSrgb,
+        135 => Format::Bc2RgbaUnorm,
+        136 => Format::Bc2RgbaUnormSrgb,
+        137 => Format::Bc3RgbaUnorm,
+        138 => Format::Bc3RgbaUnormSrgb,
+        139 => Format::Bc4RUnorm,
+        140 => Format::Bc4RSnorm,
+        141 => Format::Bc5RgUnorm,
+        142 => Format::Bc5RgSnorm,
+        143 => Format::Bc6hRgbUfloat,
+        144 => Format::Bc6hRgbFloat,
+        145 => Format::Bc7RgbaUnorm,
+        146 => Format::Bc7RgbaUnormSrgb,
+        147 => Format::Etc2Rgb8Unorm,
+        148 => Format::Etc2Rgb8UnormSrgb,
+        149 => Format::Etc2Rgb8A1Unorm,
+        150 => Format::Etc2Rgb8A1UnormSrgb,
+        151 => Format::Etc2Rgba8Unorm,
+        152 => Format::Etc2Rgba8UnormSrgb,
+        153 => Format::EacR11Unorm,
+        154 => Format::EacR11Snorm,
+        155 => Format::EacRg11Unorm,
+        156 => Format::EacRg11Snorm,
+        // ASTC LDR formats come in pairs of Unorm and sRGB, by block size
+        157..=184 => {
+            let blocks = [
+                AstcBlock::B4x4,
+                AstcBlock::B5x4,
+                AstcBlock::B5x5,
+                AstcBlock::B6x5,
+                AstcBlock::B6x6,
+                AstcBlock::B8x5,
+                AstcBlock::B8x6,
+                AstcBlock::B8x8,
+                AstcBlock::B10x5,
+                AstcBlock::B10x6,
+                AstcBlock::B10x8,
+                AstcBlock::B10x10,
+                AstcBlock::B12x10,
+                AstcBlock::B12x12,
+            ];
+            let index = vk_format - 157;
+            let channel = if index % 2 == 0 {
+                AstcChannel::Unorm
+            } else {
+                AstcChannel::UnormSrgb
+            };
+
+            Format::Astc {
+                block: blocks[index as usize / 2],
+                channel,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        encoded_image, linear_image, mip_chain, read_ktx2, Texture, TextureError, KTX2_MAGIC,
+    };
+    use crate::{
+        renderer::{target::RenderTargetDescription, TargetId},
+        FragmentColor, Quad,
+    };
+
+    /// A KTX2 file with the given VkFormat and mip levels, stored smallest first
+    fn ktx2_file(vk_format: u32, width: u32, height: u32, levels: &[Vec<u8>]) -> Vec<u8> {
+        let mut header = KTX2_MAGIC.to_vec();
+        for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0] {
+            header.extend_from_slice(&value.to_le_bytes());
+        }
+        header.resize(80, 0);
+
+        let mut offset = 80 + levels.len() * 24;
+        let mut offsets = vec![0; levels.len()];
+        for (level, data) in levels.iter().enumerate().rev() {
+            offsets[level] = offset;
+            offset += data.len();
+        }
+        for (level, data) in levels.iter().enumerate() {
+            for value in [offsets[level], data.len(), data.len()] {
+                header.extend_from_slice(&(value as u64).to_le_bytes());
+            }
+        }
+        for data in levels.iter().rev() {
+            header.extend_from_slice(data);
+        }
+
+        header
+    }
+
+    #[test]
+    fn loaded_textures_can_be_copied_into_targets() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 255, 0, 255]))
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let (source, size) = Texture::from_bytes(&png.into_inner()).unwrap();
+
+        let description = RenderTargetDescription::create_texture_target(size).unwrap();
+        let target_id = description.target_id;
+        let destination = if let TargetId::Texture(texture_id) = target_id {
+            texture_id
+        } else {
+            panic!("Texture targets are identified by their texture");
+        };
+
+        Texture::copy(&source, &destination, None).unwrap();
+        let bytes = target_id.read_region_blocking(size).unwrap();
+
+        FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .remove_target(&target_id)
+            .unwrap();
+        Texture::free(&source).unwrap();
+
+        for pixel in bytes.chunks_exact(4) {
+            assert_eq!(pixel, [0, 255, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn texture_targets_can_be_inspected_by_texture_id() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let size = Quad::from_size(8, 4);
+        let description = RenderTargetDescription::create_texture_target(size).unwrap();
+        let texture_id = if let TargetId::Texture(texture_id) = description.target_id {
+            texture_id
+        } else {
+            panic!("Texture targets are identified by their texture");
+        };
+
+        let found = (texture_id.size(), texture_id.format());
+        let renders = texture_id.has_usage(wgpu::TextureUsages::RENDER_ATTACHMENT);
+        FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .remove_target(&description.target_id)
+            .unwrap();
+
+        assert_eq!(
+            found,
+            (Some(size), Some(wgpu::TextureFormat::Rgba8UnormSrgb))
+        );
+        assert!(renders);
+        assert_eq!(texture_id.size(), None);
+    }
+
+    #[test]
+    fn ktx2_mip_levels_are_read_from_the_largest() {
+        // ASTC 4x4 sRGB, 8x8 pixels: four blocks, then one
+        let file = ktx2_file(158, 8, 8, &[vec![1; 64], vec![2; 16]]);
+
+        let ktx2 = read_ktx2(&file).unwrap();
+
+        assert_eq!(
+            ktx2.format,
+            wgpu::TextureFormat::Astc {
+                block: wgpu::AstcBlock::B4x4,
+                channel: wgpu::AstcChannel::UnormSrgb,
+            }
+        );
+        assert_eq!((ktx2.width, ktx2.height, ktx2.mip_level_count), (8, 8, 2));
+        assert_eq!(&ktx2.data[..64], &[1; 64]);
+        assert_eq!(&ktx2.data[64..], &[2; 16]);
+    }
+
+    #[test]
+    fn ktx2_formats_that_are_not_compressed_are_rejected() {
+        // VK_FORMAT_R8G8B8A8_UNORM
+        let file = ktx2_file(37, 4, 4, &[vec![0; 64]]);
+
+        let error = read_ktx2(&file).err().unwrap();
+
+        assert!(matches!(
+            error.downcast_ref::<TextureError>(),
+            Some(TextureError::UnknownCompressedFormat(_))
+        ));
+        assert!(read_ktx2(&file[..90]).is_err());
+    }
+
+    #[test]
+    fn unsupported_compressed_formats_are_reported() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+        let features = FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .device
+            .features();
+        if features.contains(wgpu::Features::TEXTURE_COMPRESSION_ETC2) {
+            return;
+        }
+
+        // ETC2 RGB8, one 4x4 block
+        let file = ktx2_file(147, 4, 4, &[vec![0; 8]]);
+
+        let error = Texture::from_ktx2_bytes(&file).err().unwrap();
+
+        assert!(matches!(
+            error.downcast_ref::<TextureError>(),
+            Some(TextureError::UnsupportedCompressedFormat(
+                wgpu::TextureFormat::Etc2Rgb8UnormSrgb
+            ))
+        ));
+    }
+
+    #[test]
+    fn full_mip_chain_level_count() {
+        assert_eq!(Texture::mip_level_count(256, 256), 9);
+        assert_eq!(Texture::mip_level_count(256, 64), 9);
+        assert_eq!(Texture::mip_level_count(300, 20), 9);
+        assert_eq!(Texture::mip_level_count(1, 1), 1);
+    }
+
+    #[test]
+    fn linear_conversion_round_trips() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let image = image::RgbaImage::from_fn(256, 1, |x, _| {
+            image::Rgba([bytes[x as usize], 0, 255, bytes[x as usize]])
+        });
+
+        for srgb in [true, false] {
+            assert_eq!(encoded_image(&linear_image(&image, srgb), srgb), image);
+        }
+    }
+
+    #[test]
+    fn mip_chain_halves_down_to_one_pixel() {
+        let image = image::RgbaImage::new(256, 64);
+
+        let chain = mip_chain(&image, true, Texture::mip_level_count(256, 64));
+
+        let sizes: Vec<(u32, u32)> = chain.iter().map(|mip| mip.dimensions()).collect();
+        assert_eq!(
+            sizes,
+            [
+                (128, 32),
+                (64, 16),
+                (32, 8),
+                (16, 4),
+                (8, 2),
+                (4, 1),
+                (2, 1),
+                (1, 1)
+            ]
+        );
+        assert!(mip_chain(&image, true, 1).is_empty());
+    }
+
+    #[test]
+    fn srgb_mips_are_averaged_in_linear_space() {
+        // Black and white, in sRGB and in linear space
+        let image = image::RgbaImage::from_fn(2, 2, |x, _| {
+            let value = if x == 0 { 0 } else { 255 };
+            image::Rgba([value, value, value, 255])
+        });
+
+        let srgb = mip_chain(&image, true, 2);
+        let linear = mip_chain(&image, false, 2);
+
+        // Half of the light is 188 in sRGB, not 128
+        let [r, g, b, a] = srgb[0].get_pixel(0, 0).0;
+        assert!((187..=189).contains(&r), "got {}", r);
+        assert_eq!((g, b, a), (r, r, 255));
+        let [r, _, _, a] = linear[0].get_pixel(0, 0).0;
+        assert!((127..=128).contains(&r), "got {}", r);
+        assert_eq!(a, 255);
+    }
+}
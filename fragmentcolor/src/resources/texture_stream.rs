@@ -0,0 +1,274 @@
+use crate::{
+    resources::texture::{is_compressed_container, Texture, TextureId, TextureOptions},
+    Quad,
+};
+use image::{DynamicImage, GenericImageView};
+use std::collections::VecDeque;
+#[cfg(not(wasm))]
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Weak},
+    task::{Poll, Waker},
+};
+
+type Error = Box<dyn std::error::Error>;
+type DecodeError = Box<dyn std::error::Error + Send + Sync>;
+
+/// An image to load with `Texture::stream()`.
+#[derive(Debug)]
+pub enum TextureInput {
+    /// An encoded image, or a DDS or KTX2 file, in memory.
+    Bytes(Vec<u8>),
+
+    /// Path to an image, or to a DDS or KTX2 file.
+    ///
+    /// Not available on the Web, which has no file system.
+    #[cfg(not(wasm))]
+    File(PathBuf),
+}
+
+impl From<Vec<u8>> for TextureInput {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+#[cfg(not(wasm))]
+impl From<PathBuf> for TextureInput {
+    fn from(path: PathBuf) -> Self {
+        Self::File(path)
+    }
+}
+
+#[cfg(not(wasm))]
+impl From<&Path> for TextureInput {
+    fn from(path: &Path) -> Self {
+        Self::File(path.to_path_buf())
+    }
+}
+
+/// A texture loaded by a `TextureStream`, with the position of its input.
+pub type LoadedTexture = (usize, Result<(TextureId, Quad), Error>);
+
+/// An input ready to be uploaded to the GPU.
+enum Decoded {
+    Image(DynamicImage),
+    // DDS and KTX2 files are uploaded as they are
+    Compressed(Vec<u8>),
+}
+
+/// Loads many textures, yielding each one as soon as it's uploaded.
+///
+/// Created by `Texture::stream()`. On native, the inputs are decoded on
+/// one thread per CPU core, in the background. The GPU upload happens
+/// when `next()` is awaited, because it goes through the Renderer.
+///
+/// On the Web there are no threads, so each `next()` decodes and uploads
+/// one input. Awaiting it in a loop still lets the app show progress
+/// between textures.
+///
+/// Textures come in the order they finish decoding. Each one carries
+/// the position of its input, so the results can be put back in order.
+pub struct TextureStream {
+    options: TextureOptions,
+    total: usize,
+    loaded: usize,
+
+    #[cfg(not(wasm))]
+    decoded: Arc<Mutex<DecodedQueue>>,
+
+    #[cfg(wasm)]
+    inputs: VecDeque<(usize, TextureInput)>,
+}
+
+/// Inputs decoded by the worker threads, waiting to be uploaded.
+#[cfg(not(wasm))]
+#[derive(Default)]
+struct DecodedQueue {
+    decoded: VecDeque<(usize, Result<Decoded, DecodeError>)>,
+    waker: Option<Waker>,
+}
+
+impl TextureStream {
+    pub(crate) fn new(inputs: Vec<TextureInput>, options: TextureOptions) -> Self {
+        let total = inputs.len();
+        let inputs: VecDeque<(usize, TextureInput)> = inputs.into_iter().enumerate().collect();
+
+        #[cfg(not(wasm))]
+        let decoded = {
+            let decoded = Arc::new(Mutex::new(DecodedQueue::default()));
+            let inputs = Arc::new(Mutex::new(inputs));
+            let workers = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(total);
+
+            for _ in 0..workers {
+                let inputs = inputs.clone();
+                let decoded = Arc::downgrade(&decoded);
+                std::thread::spawn(move || decode_inputs(inputs, decoded));
+            }
+
+            decoded
+        };
+
+        Self {
+            options,
+            total,
+            loaded: 0,
+            #[cfg(not(wasm))]
+            decoded,
+            #[cfg(wasm)]
+            inputs,
+        }
+    }
+
+    /// How many textures this stream loads in total.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// How many textures were yielded so far.
+    pub fn loaded(&self) -> usize {
+        self.loaded
+    }
+
+    /// Uploads the next decoded texture, or returns None when all are loaded.
+    pub async fn next(&mut self) -> Option<LoadedTexture> {
+        if self.loaded == self.total {
+            return None;
+        }
+
+        let (index, decoded) = self.next_decoded().await;
+        self.loaded += 1;
+
+        let texture = match decoded {
+            Ok(Decoded::Image(image)) => Texture::from_loaded_image(&image, &self.options)
+                .map(|texture_id| (texture_id, Quad::from_tuple(image.dimensions()))),
+            Ok(Decoded::Compressed(bytes)) => {
+                Texture::from_bytes_with(&bytes, self.options.clone())
+            }
+            Err(error) => Err(error as Error),
+        };
+
+        Some((index, texture))
+    }
+
+    #[cfg(not(wasm))]
+    async fn next_decoded(&mut self) -> (usize, Result<Decoded, DecodeError>) {
+        std::future::poll_fn(|cx| {
+            let mut queue = self.decoded.lock().unwrap();
+            if let Some(decoded) = queue.decoded.pop_front() {
+                return Poll::Ready(decoded);
+            }
+            queue.waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        })
+        .await
+    }
+
+    #[cfg(wasm)]
+    async fn next_decoded(&mut self) -> (usize, Result<Decoded, DecodeError>) {
+        let (index, input) = self
+            .inputs
+            .pop_front()
+            .expect("TextureStream has fewer inputs than its total");
+
+        (index, decode(input))
+    }
+}
+
+/// Decodes inputs until there are none left or the stream is dropped.
+#[cfg(not(wasm))]
+fn decode_inputs(
+    inputs: Arc<Mutex<VecDeque<(usize, TextureInput)>>>,
+    decoded: Weak<Mutex<DecodedQueue>>,
+) {
+    loop {
+        let next = inputs.lock().ok().and_then(|mut inputs| inputs.pop_front());
+        let (index, input) = if let Some(next) = next {
+            next
+        } else {
+            break;
+        };
+
+        let result = decode(input);
+
+        let decoded = if let Some(decoded) = decoded.upgrade() {
+            decoded
+        } else {
+            break;
+        };
+        let mut queue = decoded.lock().unwrap();
+        queue.decoded.push_back((index, result));
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+fn decode(input: TextureInput) -> Result<Decoded, DecodeError> {
+    let bytes = match input {
+        TextureInput::Bytes(bytes) => bytes,
+        #[cfg(not(wasm))]
+        TextureInput::File(path) => std::fs::read(path)?,
+    };
+
+    if is_compressed_container(&bytes) {
+        Ok(Decoded::Compressed(bytes))
+    } else {
+        Ok(Decoded::Image(image::load_from_memory(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FragmentColor;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = DynamicImage::new_rgba8(width, height);
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut bytes, image::ImageOutputFormat::Png)
+            .unwrap();
+
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn streamed_textures_keep_the_index_of_their_input() {
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let inputs = vec![
+            TextureInput::from(png(4, 2)),
+            TextureInput::from(b"not an image".to_vec()),
+            TextureInput::from(png(8, 8)),
+        ];
+        let mut stream = Texture::stream(inputs, TextureOptions::default());
+
+        let mut results = Vec::new();
+        while let Some(loaded) = pollster::block_on(stream.next()) {
+            results.push(loaded);
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        assert_eq!(stream.loaded(), 3);
+        assert_eq!(stream.total(), 3);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].1.as_ref().unwrap().1, Quad::from_size(4, 2));
+        assert!(results[1].1.is_err());
+        assert_eq!(results[2].1.as_ref().unwrap().1, Quad::from_size(8, 8));
+
+        for (_, result) in results {
+            if let Ok((texture_id, _)) = result {
+                Texture::free(&texture_id).unwrap();
+            }
+        }
+    }
+}
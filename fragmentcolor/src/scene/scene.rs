@@ -1,7 +1,10 @@
 use crate::{
     app::panics,
     components,
-    renderer::target::{DescribesTarget, RenderTargetDescription},
+    renderer::{
+        target::{encode_png, DescribesTarget, RenderTargetDescription},
+        RendererError,
+    },
     scene::{
         object::{ObjectId, SceneObject},
         transform::{GPUGlobalTransforms, GPULocalTransform, Transform, TransformId},
@@ -18,6 +21,8 @@ use std::{
     },
 };
 
+type Error = Box<dyn std::error::Error>;
+
 impl ops::Index<TransformId> for Vec<Transform> {
     type Output = Transform;
     fn index(&self, transform: TransformId) -> &Transform {
@@ -185,16 +190,105 @@ impl Scene {
     }
 
     /// Renders the Scene.
-    pub fn render(&self) {
-        // -> Result<(), wgpu::SurfaceError> {
-        _ = if let Ok(renderer) = FragmentColor::renderer().try_read() {
+    ///
+    /// Returns an error if a frame could not be acquired, or, on native,
+    /// if the GPU reported an error while rendering.
+    pub fn render(&self) -> Result<(), RendererError> {
+        if let Ok(renderer) = FragmentColor::renderer().try_read() {
             renderer.render(self)
         } else {
             log::warn!("Dropped Frame: Scene failed to Acquire Renderer Lock!");
-            Err(wgpu::SurfaceError::Lost)
+            Err(RendererError::Locked)
         }
     }
 
+    /// Renders many Scenes, submitting their commands to the GPU at once.
+    ///
+    /// This has less overhead than calling `render()` on each Scene
+    /// when compositing many small layers. Scenes are rendered in order.
+    /// Scenes that share a Render Target can't share its frame, so the
+    /// batch is split before them.
+    pub fn render_batch(scenes: &[&Scene]) -> Result<(), RendererError> {
+        if let Ok(renderer) = FragmentColor::renderer().try_read() {
+            renderer.render_batch(scenes)
+        } else {
+            log::warn!("Dropped Frame: Scene batch failed to Acquire Renderer Lock!");
+            Err(RendererError::Locked)
+        }
+    }
+
+    /// Renders the Scene to an offscreen image of the given size.
+    ///
+    /// Creates a temporary Texture target for the first camera, renders
+    /// one frame and returns its tightly packed RGBA8 bytes. Useful for
+    /// headless rendering, tests and batch jobs.
+    ///
+    /// The readback doesn't block on the GPU. The future polls the device
+    /// itself, so any executor can drive it, and the Renderer is released
+    /// while it waits. Use `render_to_image_blocking()` to wait instead.
+    pub async fn render_to_image(&mut self, size: Quad) -> Result<(Vec<u8>, Quad), Error> {
+        self.render_to_image_with(size, wgpu::Maintain::Poll).await
+    }
+
+    /// Same as `render_to_image()`, blocking until the GPU finishes.
+    ///
+    /// Not available on the Web, where the browser drives the GPU.
+    #[cfg(not(wasm))]
+    pub fn render_to_image_blocking(&mut self, size: Quad) -> Result<(Vec<u8>, Quad), Error> {
+        pollster::block_on(self.render_to_image_with(size, wgpu::Maintain::Wait))
+    }
+
+    async fn render_to_image_with(
+        &mut self,
+        size: Quad,
+        maintain: wgpu::Maintain,
+    ) -> Result<(Vec<u8>, Quad), Error> {
+        let description = RenderTargetDescription::create_texture_target(size)?;
+        let target_id = description.target_id;
+
+        // Sets the Scene's targets aside, so only the temporary target
+        // renders and Windows don't get an extra frame. They are restored
+        // before awaiting, so dropping the future can't lose them.
+        let targets = self.write_state().take_targets();
+        self.target(&description);
+
+        let renderer = FragmentColor::renderer();
+        let readback = if let Ok(renderer) = renderer.try_read() {
+            let readback = match renderer.render(self) {
+                Ok(()) => renderer.target_frame_readback(&target_id),
+                Err(error) => Err(error.into()),
+            };
+            // The copy is already submitted, so the target can go
+            let removed = renderer.remove_target(&target_id);
+
+            readback.and_then(|readback| removed.map(|_| readback))
+        } else {
+            Err("Renderer is locked. Cannot render image!".into())
+        };
+
+        self.write_state().restore_targets(targets);
+        let image = readback?.read(maintain).await?;
+
+        Ok((image, size))
+    }
+
+    /// Renders the Scene offscreen and encodes the result as a PNG file in memory.
+    pub async fn render_to_png(&mut self, size: Quad) -> Result<Vec<u8>, Error> {
+        let (bytes, size) = self.render_to_image(size).await?;
+        encode_png(bytes, size)
+    }
+
+    /// Renders the Scene offscreen and saves it as a PNG file.
+    ///
+    /// Not available on the Web. Use `render_to_png()` there instead.
+    #[cfg(not(wasm))]
+    pub fn save_png(&mut self, path: impl AsRef<std::path::Path>, size: Quad) -> Result<(), Error> {
+        let (bytes, size) = self.render_to_image_blocking(size)?;
+        std::fs::write(path, encode_png(bytes, size)?)?;
+
+        Ok(())
+    }
+
     /// Adds a new rendering target to the Scene.
     pub fn target<D: DescribesTarget>(&mut self, descriptor: &D) {
         if let Ok(description) = descriptor.describe_target() {
@@ -408,6 +502,11 @@ impl SceneState {
         }
     }
 
+    /// The Ids of all RenderTargets this Scene renders to.
+    pub(crate) fn target_ids(&self) -> Vec<TargetId> {
+        self.target_indices.keys().copied().collect()
+    }
+
     /// Resizes a Target Description by TargetId
     pub(crate) fn resize_target(&mut self, target_id: TargetId, size: Quad) {
         let instances = if let Some(instances) = self.target_indices.get(&target_id) {
@@ -425,6 +524,19 @@ impl SceneState {
         }
     }
 
+    /// Detaches all targets from the Scene, returning them.
+    pub(crate) fn take_targets(&mut self) -> (CameraTargets, TargetIndices) {
+        (
+            std::mem::take(&mut self.targets),
+            std::mem::take(&mut self.target_indices),
+        )
+    }
+
+    /// Replaces the Scene's targets with ones from `take_targets()`.
+    pub(crate) fn restore_targets(&mut self, targets: (CameraTargets, TargetIndices)) {
+        (self.targets, self.target_indices) = targets;
+    }
+
     /// Removes a target from the Scene.
     pub(crate) fn remove_target(&mut self, target_id: TargetId) {
         let instances = if let Some(instances) = self.target_indices.remove(&target_id) {
@@ -663,4 +775,63 @@ mod tests {
 
         assert!(state.targets.get(&camera_id).unwrap().is_empty());
     }
+
+    #[test]
+    fn test_scene_state_take_and_restore_targets() {
+        let mut scene = Scene::new_unregistered();
+        let descriptor =
+            RenderTargetDescription::create_texture_target(Quad::from_size(100, 100)).unwrap();
+        scene.target(&descriptor);
+
+        let mut state = scene.write_state();
+        let targets = state.take_targets();
+
+        assert!(state.targets.is_empty());
+        assert!(state.target_ids().is_empty());
+
+        state.restore_targets(targets);
+
+        assert_eq!(state.target_ids(), vec![descriptor.target_id]);
+    }
+
+    #[test]
+    fn dropping_render_to_image_keeps_the_scene_targets() {
+        use std::{
+            future::Future,
+            task::{Context, RawWaker, RawWakerVTable, Waker},
+        };
+
+        // Skips on machines without a GPU adapter
+        _ = FragmentColor::init_headless(Default::default());
+        if FragmentColor::adapter_info().is_none() {
+            return;
+        }
+
+        let mut scene = Scene::new_unregistered();
+        let descriptor =
+            RenderTargetDescription::create_texture_target(Quad::from_size(8, 8)).unwrap();
+        scene.target(&descriptor);
+
+        fn noop_raw_waker() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                noop_raw_waker()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+
+        // Renders, then drops the future while it may still wait for the GPU
+        let mut future = Box::pin(scene.render_to_image(Quad::from_size(8, 8)));
+        _ = future.as_mut().poll(&mut Context::from_waker(&waker));
+        drop(future);
+
+        assert_eq!(scene.read_state().target_ids(), vec![descriptor.target_id]);
+        FragmentColor::renderer()
+            .try_read()
+            .unwrap()
+            .remove_target(&descriptor.target_id)
+            .unwrap();
+    }
 }
@@ -9,6 +9,24 @@ pub struct PyWindow {
 
 unsafe impl Send for PyWindow {}
 
+#[pyclass(name = "FrameClock")]
+pub struct PyFrameClock {
+    inner: FrameClock,
+}
+
+#[pymethods]
+impl PyFrameClock {
+    #[getter]
+    pub fn delta(&self) -> f32 {
+        self.inner.delta()
+    }
+
+    #[getter]
+    pub fn fps(&self) -> f32 {
+        self.inner.fps()
+    }
+}
+
 #[derive(FromPyObject)]
 pub enum WindowSize {
     SizeTuple(u32, u32),
@@ -46,6 +64,12 @@ impl PyWindow {
         self.inner.run();
     }
 
+    pub fn clock(&self) -> PyFrameClock {
+        PyFrameClock {
+            inner: self.inner.clock(),
+        }
+    }
+
     pub fn on(&mut self, event_name: &str, callback: PyObject) -> PyResult<()> {
         let window_id = self.inner.id();
         let caller = move |event: Event| {
@@ -160,6 +184,7 @@ impl PyWindow {
 
                     Event::Destroyed => callback.call(py, (), None),
 
+                    // The delta time is available from `window.clock()`
                     Event::Draw => callback.call(py, (), None),
 
                     Event::Exit => callback.call(py, (), None),